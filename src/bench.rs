@@ -27,7 +27,7 @@ impl Benchmark {
         self.0.insert(day, part);
     }
     pub fn write(self) {
-        let mut keys = self.0.keys().map(|k| *k).collect::<Vec<_>>();
+        let mut keys = self.0.keys().copied().collect::<Vec<_>>();
         keys.sort();
         let mut ordered_map = BTreeMap::new();
         for key in keys {
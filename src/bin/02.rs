@@ -12,14 +12,14 @@ pub fn part_one(input: &str) -> Option<u32> {
             ["A", "X"] => 3 + 1,
             ["B", "Y"] => 3 + 2,
             ["C", "Z"] => 3 + 3,
-            ["A", "Z"] => 0 + 3,
-            ["B", "X"] => 0 + 1,
-            ["C", "Y"] => 0 + 2,
+            ["A", "Z"] => 3,
+            ["B", "X"] => 1,
+            ["C", "Y"] => 2,
             _ => panic!("Invalid input"),
         }
     });
 
-    return Some(answer.sum());
+    Some(answer.sum())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -30,9 +30,9 @@ pub fn part_two(input: &str) -> Option<u32> {
     let answer = vals.map(|round| {
         match [round[0], round[1]] {
             // lose
-            ["A", "X"] => 0 + 3,
-            ["B", "X"] => 0 + 1,
-            ["C", "X"] => 0 + 2,
+            ["A", "X"] => 3,
+            ["B", "X"] => 1,
+            ["C", "X"] => 2,
 
             // draw
             ["A", "Y"] => 3 + 1,
@@ -47,7 +47,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     });
 
-    return Some(answer.sum());
+    Some(answer.sum())
 }
 
 fn main() {
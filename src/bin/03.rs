@@ -8,7 +8,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     for line in lines {
         let first_half = line.split_at(line.len() / 2).0;
         let second_half = line.split_at(line.len() / 2).1;
-        let first_half: HashSet<char> = first_half.chars().into_iter().collect();
+        let first_half: HashSet<char> = first_half.chars().collect();
 
         for c in second_half.chars() {
             if first_half.contains(&c) {
@@ -28,15 +28,15 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let lines = input.lines().into_iter().collect::<Vec<_>>();
+    let lines = input.lines().collect::<Vec<_>>();
     let mut answer = vec![];
     for i in 0..(lines.len()) {
         if i % 3 != 0 {
             continue;
         }
-        let first: HashSet<char> = lines[i].chars().into_iter().collect();
-        let second: HashSet<char> = lines[i + 1].chars().into_iter().collect();
-        let third: Vec<char> = lines[i + 2].chars().into_iter().collect();
+        let first: HashSet<char> = lines[i].chars().collect();
+        let second: HashSet<char> = lines[i + 1].chars().collect();
+        let third: Vec<char> = lines[i + 2].chars().collect();
 
         for c in third {
             if first.contains(&c) && second.contains(&c) {
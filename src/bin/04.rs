@@ -1,4 +1,4 @@
-use std::ops::RangeInclusive;
+use advent_of_code::helpers::Interval;
 
 pub fn part_one(input: &str) -> Option<usize> {
     let input = parse(input);
@@ -6,7 +6,7 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(
         input
             .iter()
-            .filter(|(a, b)| a.clone().all(|n| b.contains(&n)) || b.clone().all(|n| a.contains(&n)))
+            .filter(|(a, b)| a.contains_interval(b) || b.contains_interval(a))
             .count(),
     )
 }
@@ -14,12 +14,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 pub fn part_two(input: &str) -> Option<usize> {
     let input = parse(input);
 
-    Some(
-        input
-            .iter()
-            .filter(|(a, b)| a.clone().any(|n| b.contains(&n)) || b.clone().any(|n| a.contains(&n)))
-            .count(),
-    )
+    Some(input.iter().filter(|(a, b)| a.overlaps(b)).count())
 }
 
 fn main() {
@@ -28,16 +23,18 @@ fn main() {
     advent_of_code::solve!(2, 4, part_two, input);
 }
 
-fn parse(input: &str) -> Vec<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+fn parse(input: &str) -> Vec<(Interval, Interval)> {
     input
         .lines()
         .map(|l| {
-            let parts: Vec<usize> = l
+            let parts: Vec<isize> = l
                 .split(",")
-                .map(|p| p.split("-").map(|n| n.parse::<usize>().unwrap()))
-                .flatten()
+                .flat_map(|p| p.split("-").map(|n| n.parse::<isize>().unwrap()))
                 .collect();
-            (parts[0]..=parts[1], parts[2]..=parts[3])
+            (
+                Interval::from(parts[0]..=parts[1]),
+                Interval::from(parts[2]..=parts[3]),
+            )
         })
         .collect()
 }
@@ -1,3 +1,6 @@
+use advent_of_code::helpers::parse;
+use nom::{bytes::complete::tag, sequence::preceded, IResult};
+
 pub fn part_one(input: &str) -> Option<String> {
     let (mut stacks, moves) = parse(input);
 
@@ -43,52 +46,41 @@ struct Move {
     amount: usize,
 }
 
-// that was painful
 fn parse(input: &str) -> (Vec<Stack>, Vec<Move>) {
-    let parts: Vec<_> = input.split("\n\n").collect();
-    let mut stacks: Vec<Vec<char>> = parts[0]
-        .lines()
-        .map(|l| {
-            l.replace("   ", "0")
-                .replace("[", "")
-                .replace("]", "")
-                .replace(" ", "")
-        })
-        .map(|l| l.chars().collect())
-        .collect();
-
-    stacks.remove(stacks.len() - 1);
-
-    let moves = parts[1].lines().map(|l| l.into()).collect();
-
-    let mut stacks_new: Vec<Stack> = vec![Vec::new(); stacks[0].len()];
-
-    let min = stacks.iter().map(|s| s.len()).min().unwrap();
-
-    for i in 0..min {
-        for j in stacks.iter().rev().into_iter() {
-            let char = j[i];
-            if char == '0' {
-                continue;
+    let mut blocks = parse::blocks(input);
+    let (drawing, moves) = (blocks.next().unwrap(), blocks.next().unwrap());
+
+    // the last line only numbers the stacks, and the crate letters sit at columns 1, 5, 9, ...
+    let mut rows = drawing.lines().rev();
+    let count = parse::integers::<usize>(rows.next().unwrap()).len();
+    let mut stacks: Vec<Stack> = vec![Vec::new(); count];
+    for row in rows {
+        for (stack, c) in row.chars().skip(1).step_by(4).enumerate() {
+            if c.is_ascii_alphabetic() {
+                stacks[stack].push(c);
             }
-            stacks_new[i].push(char);
         }
     }
 
-    (stacks_new, moves)
+    let moves = parse::lines(moves, move_line).unwrap_or_else(|e| panic!("{}", e));
+
+    (stacks, moves)
 }
 
-impl From<&str> for Move {
-    fn from(value: &str) -> Self {
-        let (amount, from, to) =
-            sscanf::scanf!(value, "move {} from {} to {}", usize, usize, usize).unwrap();
+/// `move 1 from 2 to 1`
+fn move_line(input: &str) -> IResult<&str, Move> {
+    let (input, amount) = preceded(tag("move "), parse::unsigned)(input)?;
+    let (input, from) = preceded(tag(" from "), parse::unsigned::<usize>)(input)?;
+    let (input, to) = preceded(tag(" to "), parse::unsigned::<usize>)(input)?;
 
-        Self {
+    Ok((
+        input,
+        Move {
             from: from - 1,
             to: to - 1,
             amount,
-        }
-    }
+        },
+    ))
 }
 
 #[cfg(test)]
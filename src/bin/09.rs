@@ -1,15 +1,20 @@
+use advent_of_code::helpers::{
+    viz::{Color, Frame, Player},
+    Direction, Point, Rect, SparseGrid,
+};
+
 pub fn part_one(input: &str) -> Option<usize> {
     let directions = parse(input);
-    let mut set: HashSet<Point> = HashSet::new();
-    let mut head = Point(0, 0);
-    let mut tail = Point(0, 0);
+    let mut set = SparseGrid::new().with_flipped_y();
+    let mut head = Point::new(0, 0);
+    let mut tail = Point::new(0, 0);
 
     for d in directions {
-        head.move_direction(d);
+        head += d;
 
-        tail.follow(head);
+        follow(&mut tail, head);
 
-        set.insert(tail);
+        set.insert(tail, '#');
     }
 
     Some(set.len())
@@ -17,22 +22,12 @@ pub fn part_one(input: &str) -> Option<usize> {
 
 pub fn part_two(input: &str) -> Option<usize> {
     let directions = parse(input);
-    let mut set: HashSet<Point> = HashSet::new();
-    let mut knots: [Point; 10] = (0..10)
-        .map(|_| Point(0, 0))
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
+    let mut set = SparseGrid::new().with_flipped_y();
+    let mut knots = [Point::new(0, 0); 10];
 
     for d in directions {
-        knots[0].move_direction(d);
-        let mut last = knots[0];
-        for i in 1..10 {
-            knots[i].follow(last);
-            last = knots[i];
-        }
-
-        set.insert(knots[9]);
+        pull(&mut knots, d);
+        set.insert(knots[9], '#');
     }
 
     Some(set.len())
@@ -40,45 +35,66 @@ pub fn part_two(input: &str) -> Option<usize> {
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 9);
+    if let Some(player) = Player::from_args() {
+        player.play(frames(input));
+    }
     advent_of_code::solve!(1, 9, part_one, input);
     advent_of_code::solve!(2, 9, part_two, input);
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Point(isize, isize);
-
-impl Point {
-    // cant call it 'move' :(
-    fn move_direction(&mut self, direction: Direction) {
-        match direction {
-            Up => self.1 += 1,
-            Down => self.1 -= 1,
-            Left => self.0 -= 1,
-            Right => self.0 += 1,
-        }
+/// moves the head in `d` and every other knot after the one in front of it
+fn pull(knots: &mut [Point], d: Direction) {
+    knots[0] += d;
+    let mut last = knots[0];
+    for knot in knots.iter_mut().skip(1) {
+        follow(knot, last);
+        last = *knot;
     }
-    fn follow(&mut self, point: Point) {
-        let distance_x = (point.0 - self.0).abs();
-        let distance_y = (point.1 - self.1).abs();
-        let distance = distance_x + distance_y;
-
-        let is_connected = distance <= 1 || (distance == 2 && distance_x == distance_y);
-        if !is_connected {
-            let x = point.0 - self.0;
-            let y = point.1 - self.1;
-
-            self.0 += x.signum();
-            self.1 += y.signum();
-        }
+}
+
+// the tail moves one step (diagonally if it needs to) towards the head when they stop touching
+fn follow(knot: &mut Point, head: Point) {
+    let distance = head - *knot;
+    if distance.x.abs() > 1 || distance.y.abs() > 1 {
+        *knot += distance.signum();
     }
 }
 
+/// the rope from part two after every step, in a window that follows the head
+fn frames(input: &str) -> impl Iterator<Item = Frame> {
+    let mut visited = SparseGrid::new().with_flipped_y();
+    let mut knots = [Point::new(0, 0); 10];
+
+    parse(input).into_iter().enumerate().map(move |(i, d)| {
+        pull(&mut knots, d);
+        visited.insert(knots[9], '#');
+
+        let view = Rect::from_size(knots[0] - Point::new(40, 12), 80, 25);
+        let mut board = visited.clone();
+        for (n, knot) in knots.iter().enumerate().rev() {
+            let label = if n == 0 {
+                'H'
+            } else {
+                char::from_digit(n as u32, 10).unwrap()
+            };
+            board.insert(*knot, label);
+        }
+
+        // y is flipped, so the top line of the text is the window's highest y
+        let on_screen = |p: &Point| Point::new(p.x - view.min.x, view.max.y - p.y);
+        Frame::new(board.render(view))
+            .highlight(knots.iter().map(on_screen), Color::Yellow)
+            .highlight([on_screen(&knots[9])], Color::Red)
+            .with_caption(format!("step {}, tail visited {}", i + 1, visited.len()))
+    })
+}
+
 fn parse(input: &str) -> Vec<Direction> {
     let mut ans = Vec::new();
     for l in input.lines() {
-        let parts: Vec<_> = l.split(" ").collect();
-        let dir = Direction::from(parts[0]);
-        let amount = parts[1].parse::<usize>().unwrap();
+        let (dir, amount) = l.split_once(' ').expect("direction and amount");
+        let dir: Direction = dir.parse().expect("valid direction");
+        let amount = amount.parse::<usize>().unwrap();
         for _ in 0..amount {
             ans.push(dir);
         }
@@ -87,30 +103,6 @@ fn parse(input: &str) -> Vec<Direction> {
     ans
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-use std::collections::HashSet;
-
-use Direction::*;
-
-impl From<&str> for Direction {
-    fn from(input: &str) -> Self {
-        match input {
-            "R" => Right,
-            "L" => Left,
-            "U" => Up,
-            "D" => Down,
-            _ => panic!("Invalid direction"),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
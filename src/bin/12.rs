@@ -1,159 +1,83 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashSet},
-    fmt::Display,
-    ops::Index,
+use advent_of_code::helpers::{
+    search,
+    viz::{Color, Frame, Player},
+    Grid, Point,
 };
-
-use rayon::prelude::*;
+use std::{cmp::Ordering, fmt::Display};
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let map = Map::from(input.to_string());
-    let start = map.get_start();
-    let end = map.get_end();
+    let map = parse(input);
+    let start = map.find(|&l| l == START).expect("start");
+    let end = map.find(|&l| l == END).expect("end");
 
-    pathfind(map, start, end)
+    pathfind(&map, [start], end)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let map = Map::from(input.to_string());
-    let mut starts = vec![map.get_start()];
-    let end = map.get_end();
+    let map = parse(input);
+    let end = map.find(|&l| l == END).expect("end");
+    let starts = map.positions(|&l| l == START || l == Location(0));
 
-    for (i, row) in map.locations.iter().enumerate() {
-        for (j, loc) in row.iter().enumerate() {
-            if *loc == Location(0) {
-                starts.push((i, j));
-            }
-        }
-    }
-
-    // there's a much faster way to do this but rust is already fast + rayon parellelizes it
-    // so i dont rly care enough to do it faster
-    Some(
-        starts
-            .par_iter()
-            .filter_map(|&start| pathfind(map.clone(), start, end))
-            .min()
-            .expect("min") as u32,
-    )
+    // searching from every start at once finds the closest one in a single pass
+    pathfind(&map, starts, end)
 }
 
-fn pathfind(map: Map, start: Point, end: Point) -> Option<u32> {
-    let mut heap: BinaryHeap<Node> = vec![Node {
-        cost: 0,
-        point: start,
-    }]
-    .into();
-    let mut visited = HashSet::new();
-    visited.insert(start);
-
-    while let Some(Node { cost, point }) = heap.pop() {
-        if point == end {
-            return Some(cost);
-        }
-
-        let candidates = map.valid_moves(point);
-        for candidate in candidates {
-            if visited.insert(candidate) {
-                heap.push(Node {
-                    cost: cost + 1,
-                    point: candidate,
-                });
-            }
-        }
-    }
+fn pathfind(map: &Map, starts: impl IntoIterator<Item = Point>, end: Point) -> Option<u32> {
+    search::bfs(
+        starts,
+        |&point| valid_moves(map, point),
+        |&point| point == end,
+    )
+    .map(|(cost, _)| cost as u32)
+}
+
+/// the shortest path from part one, a step at a time
+fn frames(input: &str) -> impl Iterator<Item = Frame> {
+    let map = parse(input);
+    let start = map.find(|&l| l == START).expect("start");
+    let end = map.find(|&l| l == END).expect("end");
+    let (_, path) = search::bfs(
+        [start],
+        |&point| valid_moves(&map, point),
+        |&point| point == end,
+    )
+    .expect("a path to the end");
 
-    None
+    let text = map.to_string();
+    (1..=path.len()).map(move |len| {
+        Frame::new(text.clone())
+            .highlight(path[..len].iter().copied(), Color::Green)
+            .highlight([path[len - 1]], Color::Yellow)
+            .with_caption(format!("step {}", len - 1))
+    })
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 12);
+    if let Some(player) = Player::from_args() {
+        player.play(frames(input));
+    }
     advent_of_code::solve!(1, 12, part_one, input);
     advent_of_code::solve!(2, 12, part_two, input);
 }
 
-#[derive(Debug, Clone)]
-struct Map {
-    locations: Vec<Vec<Location>>,
-    rows: usize,
-    cols: usize,
-}
-
-#[derive(Hash, Debug, Clone, Copy, PartialEq, Eq)]
-struct Node {
-    cost: u32,
-    point: Point,
-}
+type Map = Grid<Location>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Location(u8);
 
-type Point = (usize, usize);
-
 const START: Location = Location(26);
 const END: Location = Location(27);
 
-impl Map {
-    fn neighbors(&self, point: Point) -> Vec<Point> {
-        let mut moves = Vec::new();
-        let (row, col) = point;
-        if row > 0 {
-            moves.push((row - 1, col));
-        }
-        if row < self.rows - 1 {
-            moves.push((row + 1, col));
-        }
-        if col > 0 {
-            moves.push((row, col - 1));
-        }
-        if col < self.cols - 1 {
-            moves.push((row, col + 1));
-        }
-        moves
-    }
-
-    fn valid_moves(&self, point: Point) -> Vec<Point> {
-        let neighbors = self.neighbors(point);
-        let current = self[point];
-        neighbors
-            .into_iter()
-            .filter(|&p| current > self[p])
-            .collect()
-    }
-    fn get_start(&self) -> Point {
-        for (row, r) in self.locations.iter().enumerate() {
-            for (col, c) in r.iter().enumerate() {
-                if *c == START {
-                    return (row, col);
-                }
-            }
-        }
-        panic!("No start found");
-    }
-    fn get_end(&self) -> Point {
-        for (row, r) in self.locations.iter().enumerate() {
-            for (col, c) in r.iter().enumerate() {
-                if *c == END {
-                    return (row, col);
-                }
-            }
-        }
-        panic!("No end found");
-    }
-}
-
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+fn parse(input: &str) -> Map {
+    Grid::parse(input, Location::from)
 }
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // intentionally reversed
-        other.cost.cmp(&self.cost)
-    }
+fn valid_moves(map: &Map, point: Point) -> Vec<Point> {
+    let current = map[point];
+    map.neighbors_of(point)
+        .filter(|&p| current > map[p])
+        .collect()
 }
 
 impl PartialOrd for Location {
@@ -173,9 +97,7 @@ impl Ord for Location {
             } else {
                 Less
             }
-        } else if other == &START {
-            Greater
-        } else if self == &END {
+        } else if other == &START || self == &END {
             Greater
         } else if other == &END {
             if self.0 >= 24 {
@@ -191,52 +113,14 @@ impl Ord for Location {
     }
 }
 
-impl Index<Point> for Map {
-    type Output = Location;
-
-    fn index(&self, index: Point) -> &Self::Output {
-        &self.locations[index.0][index.1]
-    }
-}
-
-impl Display for Map {
+impl Display for Location {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.locations {
-            for point in row {
-                write!(f, "{}", Into::<String>::into(*point))?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
-    }
-}
-
-impl From<String> for Map {
-    fn from(input: String) -> Self {
-        let mut locations = Vec::new();
-        for line in input.lines() {
-            let mut row = Vec::new();
-            for c in line.chars() {
-                row.push(Location::from(c));
-            }
-            locations.push(row);
-        }
-        Map {
-            rows: locations.len(),
-            cols: locations[0].len(),
-            locations,
-        }
-    }
-}
-
-impl Into<String> for Location {
-    fn into(self) -> String {
-        if self == START {
-            "S".to_string()
-        } else if self == END {
-            "E".to_string()
+        if *self == START {
+            write!(f, "S")
+        } else if *self == END {
+            write!(f, "E")
         } else {
-            String::from(('a' as u8 + self.0) as char)
+            write!(f, "{}", (b'a' + self.0) as char)
         }
     }
 }
@@ -247,7 +131,7 @@ impl From<char> for Location {
         } else if s == 'E' {
             END
         } else {
-            Location(s as u8 - 'a' as u8)
+            Location(s as u8 - b'a')
         }
     }
 }
@@ -1,236 +1,354 @@
-use regex::Regex;
+use advent_of_code::helpers::{parse, search, Graph, Interner};
+use nom::{
+    branch::alt, bytes::complete::tag, character::complete::alpha1, sequence::preceded, IResult,
+};
 use std::{
-    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::Display,
     ops::{Index, IndexMut},
 };
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (all, map) = parse(input);
-    let start: State = all.into();
-
-    let mut queue: BinaryHeap<_> = vec![start].into();
-    let mut max_pressure = 0;
-    let mut set: HashSet<State> = HashSet::new();
+    let tunnels = Tunnels::new(parse(input));
 
-    let distances = distances(&map);
-
-    while let Some(state) = queue.pop() {
-        if !set.insert(state.clone()) {
-            continue;
-        }
+    tunnels.best_by_opened(30).into_iter().max()
+}
 
-        let moves = state.moves(&map, &distances);
-        // no moves that make sense, so we prune branch + check max ticks
-        if moves.len() == 0 {
-            let mut new_state = state.clone();
-            new_state.tick(&map, 30 - new_state.minute);
-            if new_state.pressure > max_pressure {
-                max_pressure = new_state.pressure;
-            }
-            continue;
-        }
+pub fn part_two(input: &str) -> Option<u32> {
+    let tunnels = Tunnels::new(parse(input));
+    let best = at_most(tunnels.best_by_opened(26));
 
-        let new_states = moves
-            .into_iter()
-            .map(|m| calculate_move(m, &map))
-            .filter_map(|m| {
-                return match m {
-                    MoveResult::More(state) => Some(state),
-                    MoveResult::Done(pressure) => {
-                        max_pressure = max_pressure.max(pressure);
-                        None
-                    }
-                };
-            });
+    // you and the elephant each go your own way, so neither opens anything the other did
+    let everything = best.len() - 1;
+    (0..best.len())
+        .map(|mine| best[mine] + best[everything ^ mine])
+        .max()
+}
 
-        queue.extend(new_states);
-    }
+/// what you do each minute in part one
+fn explain_one(input: &str) -> Plan {
+    let tunnels = Tunnels::new(parse(input));
+    let exact = tunnels.best_by_opened(30);
+    let most = exact.iter().max().copied().unwrap_or_default();
 
-    Some(max_pressure as u32)
+    tunnels.plan(30, exact_subset(&exact, most, Opened::MAX))
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    None
+/// what you and the elephant each do every minute in part two
+fn explain_two(input: &str) -> [Plan; 2] {
+    let tunnels = Tunnels::new(parse(input));
+    let exact = tunnels.best_by_opened(26);
+    let best = at_most(exact.clone());
+
+    let everything = best.len() - 1;
+    let mine = (0..best.len())
+        .max_by_key(|&mine| best[mine] + best[everything ^ mine])
+        .unwrap_or_default();
+    let theirs = everything ^ mine;
+
+    [mine, theirs].map(|within| {
+        let opened = exact_subset(&exact, best[within], within as Opened);
+        tunnels.plan(26, opened)
+    })
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 16);
+    if pico_args::Arguments::from_env().contains("--explain") {
+        let [you, elephant] = explain_two(input);
+        let plans = [
+            ("Part 1, you", explain_one(input)),
+            ("Part 2, you", you),
+            ("Part 2, the elephant", elephant),
+        ];
+        for (who, plan) in plans {
+            println!("{} ({} released):\n{}", who, plan.released(), plan);
+        }
+    }
+
     advent_of_code::solve!(1, 16, part_one, input);
     advent_of_code::solve!(2, 16, part_two, input);
 }
 
-fn parse(input: &str) -> (Vec<Valve>, Map) {
+/// every valve, indexable by its [`Name`], and what they're called
+fn parse(input: &str) -> (Vec<Valve>, Interner) {
     let mut valves = Vec::new();
-    let mut map = HashMap::new();
-    let mut names: HashMap<&str, Name> = vec![("AA", Name(0))].into_iter().collect();
-
-    // this next 10 lines of code made me want to die..
-    let re = Regex::new("[A-Z]{2}").unwrap();
-    let mut captures = re
-        .find_iter(input)
-        .map(|i| i.as_str())
-        .collect::<HashSet<_>>();
-    captures.remove("AA");
-    let mut index = 0;
-    for name in captures {
-        index += 1;
-        names.insert(name, Name(index));
+    // the start has to be Name(0)
+    let mut names: Interner = ["AA"].into_iter().collect();
+
+    let lines = parse::lines(input, valve_line).unwrap_or_else(|e| panic!("{}", e));
+    for (name, flow_rate, paths) in lines {
+        valves.push(Valve {
+            name: Name(names.intern(name) as u8),
+            flow_rate,
+            paths: paths
+                .into_iter()
+                .map(|path| Name(names.intern(path) as u8))
+                .collect(),
+        });
     }
 
-    for line in input.lines() {
-        let line = line
-            .to_string()
-            .replace("tunnel ", "tunnels ")
-            .replace("leads ", "lead ")
-            .replace("valve ", "valves ");
-        let valve = string_to_valve(line, &names);
-        valves.push(valve.clone());
-        map.insert(valve.name.clone().into(), valve);
-    }
-
-    (valves, map)
+    valves.sort_by_key(|v| v.name);
+    (valves, names)
 }
 
-fn calculate_move(mut value: Move, map: &Map) -> MoveResult {
-    let time_remaining = 30 - value.start_state.minute;
-    // if we can't make it to our goal in time, just tick and return
-    if time_remaining <= value.distance {
-        value.start_state.tick(map, time_remaining);
-        return MoveResult::Done(value.start_state.pressure);
+/// the minutes to walk between any two valves
+fn distances(valves: &[Valve]) -> Distances {
+    let mut graph = Graph::new(valves.len());
+    for valve in valves {
+        for tunnel in valve.paths.iter() {
+            graph.add_undirected_edge(valve.name.0 as usize, tunnel.0 as usize);
+        }
     }
 
-    let mut state = value.start_state;
-    state.move_to(value.end);
-    state.tick(map, value.distance + 1);
-    state.open_valve(value.end);
+    graph
+        .shortest_paths()
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|d| d.map_or(u8::MAX / 2, |d| d as u8))
+                .collect()
+        })
+        .collect()
+}
 
-    MoveResult::More(state)
+/// a set of valves worth opening, one bit per index into [`Tunnels::flow_rates`]
+type Opened = u16;
+
+/// the volcano boiled down to the valves worth opening. the ones with no flow are only ever
+/// walked through, so they're left out
+struct Tunnels {
+    valves: Vec<Valve>,
+    names: Interner,
+    worth_opening: Vec<Name>,
+    flow_rates: Vec<u32>,
+    /// the minutes it takes to walk from one valve to another and open it. the start is the last
+    /// row, since it's never opened
+    costs: Vec<Vec<u8>>,
 }
 
-// Floyd Warshell algo
-fn distances(map: &Map) -> Distances {
-    let num = map.len();
-    let mut valve_distances = vec![vec![u8::MAX / 2; num]; num];
+impl Tunnels {
+    fn new((valves, names): (Vec<Valve>, Interner)) -> Self {
+        let distances = distances(&valves);
+        let worth_opening: Vec<Name> = valves
+            .iter()
+            .filter(|v| v.flow_rate > 0)
+            .map(|v| v.name)
+            .collect();
+        assert!(
+            worth_opening.len() <= Opened::BITS as usize,
+            "too many valves with flow"
+        );
+
+        let costs = worth_opening
+            .iter()
+            .chain([&Name(0)])
+            .map(|&a| worth_opening.iter().map(|&b| distances[a][b] + 1).collect())
+            .collect();
 
-    for (name, valve) in map {
-        for tunnel in valve.paths.iter() {
-            valve_distances[*name][*tunnel] = 1;
-            valve_distances[*tunnel][*name] = 1;
+        Self {
+            flow_rates: worth_opening
+                .iter()
+                .map(|&n| valves[n].flow_rate as u32)
+                .collect(),
+            valves,
+            names,
+            worth_opening,
+            costs,
         }
     }
 
-    for i in 0..num {
-        valve_distances[i][i] = 0;
+    fn start(&self) -> usize {
+        self.flow_rates.len()
+    }
+
+    /// the most pressure one agent can let out in `minutes` for every set of valves, indexed by
+    /// the set. sets it can't open exactly in time are 0
+    fn best_by_opened(&self, minutes: u8) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.flow_rates.len()];
+        self.visit(self.start(), minutes, 0, 0, &mut best);
+        best
     }
 
-    for k in 0..num {
-        for i in 0..num {
-            for j in 0..num {
-                valve_distances[i][j] =
-                    valve_distances[i][j].min(valve_distances[i][k] + valve_distances[k][j]);
+    fn visit(&self, at: usize, left: u8, opened: Opened, pressure: u32, best: &mut [u32]) {
+        let most = &mut best[opened as usize];
+        *most = pressure.max(*most);
+
+        for (next, &cost) in self.costs[at].iter().enumerate() {
+            let bit = 1 << next;
+            // opening it with no time left doesn't let anything out
+            if opened & bit != 0 || cost >= left {
+                continue;
             }
+
+            let left = left - cost;
+            let pressure = pressure + self.flow_rates[next] * left as u32;
+            self.visit(next, left, opened | bit, pressure, best);
         }
     }
-
-    valve_distances
 }
 
-type Distances = Vec<Vec<u8>>;
-type Map = HashMap<Name, Valve>;
+impl Tunnels {
+    /// what one agent does each minute to let the most out by opening exactly `opened`
+    fn plan(&self, minutes: u8, opened: Opened) -> Plan {
+        let mut order = Vec::new();
+        let mut best = (0, Vec::new());
+        self.route(self.start(), minutes, opened, 0, &mut order, &mut best);
+
+        let mut plan = Plan::default();
+        let mut at = Name(0);
+        let mut flow = 0;
+        let mut released = 0;
+        let mut step = |plan: &mut Plan, at: Name, action: Action, flow: u32| {
+            released += flow;
+            plan.0.push(Step {
+                minute: plan.0.len() as u8 + 1,
+                at: self.names[at.0 as usize].to_string(),
+                action,
+                released,
+            });
+        };
+
+        for next in best.1 {
+            let to = self.worth_opening[next];
+            let (_, path) = search::bfs([at], |&n| self.valves[n].paths.clone(), |&n| n == to)
+                .expect("every valve worth opening can be reached");
+            for &n in &path[1..] {
+                step(&mut plan, n, Action::Move, flow);
+            }
+            step(&mut plan, to, Action::Open, flow);
+            flow += self.flow_rates[next];
+            at = to;
+        }
+        while plan.0.len() < minutes as usize {
+            step(&mut plan, at, Action::Wait, flow);
+        }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Move {
-    distance: u8,
-    end: Name,
-    start_state: State,
-}
+        plan
+    }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct State {
-    current: Name,
-    open: Vec<Name>,
-    closed: Vec<Name>,
-    minute: u8,
-    pressure: usize,
+    /// the order of opening everything `left` to open that lets out the most
+    fn route(
+        &self,
+        at: usize,
+        minutes: u8,
+        left: Opened,
+        pressure: u32,
+        order: &mut Vec<usize>,
+        best: &mut (u32, Vec<usize>),
+    ) {
+        if left == 0 {
+            if pressure >= best.0 {
+                *best = (pressure, order.clone());
+            }
+            return;
+        }
+
+        for (next, &cost) in self.costs[at].iter().enumerate() {
+            let bit = 1 << next;
+            if left & bit == 0 || cost >= minutes {
+                continue;
+            }
+
+            let minutes = minutes - cost;
+            order.push(next);
+            let pressure = pressure + self.flow_rates[next] * minutes as u32;
+            self.route(next, minutes, left ^ bit, pressure, order, best);
+            order.pop();
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Valve {
-    name: Name,
-    paths: Vec<Name>,
-    flow_rate: u8,
+/// a set within `within` that lets out exactly `pressure` on its own
+fn exact_subset(exact: &[u32], pressure: u32, within: Opened) -> Opened {
+    let within = within & (exact.len() - 1) as Opened;
+    let mut set = within;
+    loop {
+        if exact[set as usize] == pressure {
+            return set;
+        }
+        if set == 0 {
+            panic!("no set lets out {} on its own", pressure);
+        }
+        set = (set - 1) & within;
+    }
 }
 
-#[derive(Debug, Clone)]
-enum MoveResult {
-    More(State),
-    Done(usize),
+/// turns the best for exactly each set into the best for each set or any subset of it
+fn at_most(mut best: Vec<u32>) -> Vec<u32> {
+    let bits = best.len().trailing_zeros();
+    for bit in (0..bits).map(|b| 1 << b) {
+        for opened in 0..best.len() {
+            if opened & bit != 0 {
+                best[opened] = best[opened].max(best[opened ^ bit]);
+            }
+        }
+    }
+    best
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
-struct Name(u8);
+type Distances = Vec<Vec<u8>>;
 
-impl State {
-    fn tick(&mut self, map: &Map, times: u8) {
-        self.minute += times;
-        self.pressure += self
-            .open
-            .iter()
-            .map(|n| map.get(n).unwrap())
-            .map(|v| v.flow_rate)
-            .sum::<u8>() as usize
-            * times as usize;
-    }
+/// one agent's moves, minute by minute
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Plan(Vec<Step>);
 
-    fn move_to(&mut self, to: Name) {
-        self.current = to;
-    }
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    minute: u8,
+    /// where the agent is at the end of the minute
+    at: String,
+    action: Action,
+    /// the pressure let out so far by the valves this agent opened
+    released: u32,
+}
 
-    fn open_valve(&mut self, name: Name) {
-        self.open.push(name);
-        self.closed.retain(|v| v != &name);
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Move,
+    Open,
+    Wait,
+}
 
-    fn moves(&self, map: &Map, distances: &Distances) -> Vec<Move> {
-        self.closed
-            .iter()
-            .map(|n| map.get(n).unwrap())
-            .filter(|v| v.flow_rate > 0)
-            .map(|v| {
-                let distance = distances[self.current][v.name];
-
-                Move {
-                    distance,
-                    end: v.name.clone(),
-                    start_state: self.clone(),
-                }
-            })
-            .collect()
+impl Plan {
+    fn released(&self) -> u32 {
+        self.0.last().map_or(0, |s| s.released)
     }
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.minute.cmp(&self.minute)
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.0 {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
     }
 }
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.minute.cmp(&other.minute))
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self.action {
+            Action::Move => "move to",
+            Action::Open => "open",
+            Action::Wait => "wait at",
+        };
+        write!(
+            f,
+            "minute {:>2}: {} {}, {} released",
+            self.minute, action, self.at, self.released
+        )
     }
 }
 
-impl From<Vec<Valve>> for State {
-    fn from(valves: Vec<Valve>) -> Self {
-        Self {
-            current: Name(0),
-            closed: valves.iter().map(|v| v.name.clone()).collect(),
-            open: Vec::new(),
-            minute: 0,
-            pressure: 0,
-        }
-    }
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Valve {
+    name: Name,
+    paths: Vec<Name>,
+    flow_rate: u8,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
+struct Name(u8);
+
 impl<T> Index<Name> for Vec<T> {
     type Output = T;
 
@@ -245,32 +363,33 @@ impl<T> IndexMut<Name> for Vec<T> {
     }
 }
 
-fn string_to_valve(value: String, names: &HashMap<&str, Name>) -> Valve {
-    let (name, flow_rate, paths) = sscanf::sscanf!(
-        value,
-        "Valve {} has flow rate={}; tunnels lead to valves {}",
-        str,
-        u8,
-        String
-    )
-    .expect("valid input");
-
-    let paths = paths
-        .split(",")
-        .map(|s| *names.get(s.trim()).unwrap())
-        .collect();
-
-    Valve {
-        name: names[name],
-        flow_rate,
-        paths,
-    }
+/// `Valve AA has flow rate=0; tunnels lead to valves DD, II, BB`, or `tunnel leads to valve`
+/// when there's only one
+fn valve_line(input: &str) -> IResult<&str, (&str, u8, Vec<&str>)> {
+    let (input, name) = preceded(tag("Valve "), alpha1)(input)?;
+    let (input, flow_rate) = preceded(tag(" has flow rate="), parse::unsigned)(input)?;
+    let (input, _) = alt((
+        tag("; tunnels lead to valves "),
+        tag("; tunnel leads to valve "),
+    ))(input)?;
+    let (input, paths) = parse::comma_list(alpha1)(input)?;
+
+    Ok((input, (name, flow_rate, paths)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// when each valve gets opened
+    fn opened(plan: &Plan) -> Vec<(u8, &str)> {
+        plan.0
+            .iter()
+            .filter(|s| s.action == Action::Open)
+            .map(|s| (s.minute, s.at.as_str()))
+            .collect()
+    }
+
     #[test]
     fn test_16_part_one() {
         let input = advent_of_code::read_file("examples", 16);
@@ -280,6 +399,74 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 16);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Some(1707));
+    }
+
+    #[test]
+    fn test_tunnels() {
+        let input = advent_of_code::read_file("examples", 16);
+        let tunnels = Tunnels::new(parse(&input));
+
+        // DD, BB, CC, EE, HH and JJ, in the order their names first show up
+        assert_eq!(tunnels.flow_rates, vec![20, 13, 2, 3, 22, 21]);
+        // from AA, DD is one step away and HH is five
+        assert_eq!(tunnels.costs[tunnels.start()][0], 2);
+        assert_eq!(tunnels.costs[tunnels.start()][4], 6);
+
+        let best = tunnels.best_by_opened(30);
+        // just opening DD straight away
+        assert_eq!(best[0b1], 20 * 28);
+        assert_eq!(at_most(best)[0b111111], 1651);
+    }
+
+    #[test]
+    fn test_explain_one() {
+        let input = advent_of_code::read_file("examples", 16);
+        let plan = explain_one(&input);
+
+        assert_eq!(
+            opened(&plan),
+            vec![
+                (2, "DD"),
+                (5, "BB"),
+                (9, "JJ"),
+                (17, "HH"),
+                (21, "EE"),
+                (24, "CC")
+            ]
+        );
+        assert_eq!(plan.0.len(), 30);
+        assert_eq!(
+            plan.0[..5]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "minute  1: move to DD, 0 released",
+                "minute  2: open DD, 0 released",
+                "minute  3: move to CC, 20 released",
+                "minute  4: move to BB, 40 released",
+                "minute  5: open BB, 60 released",
+            ]
+        );
+        assert_eq!(plan.released(), 1651);
+    }
+
+    #[test]
+    fn test_explain_two() {
+        let input = advent_of_code::read_file("examples", 16);
+        let plans = explain_two(&input);
+
+        let mut opened: Vec<_> = plans.iter().map(opened).collect();
+        opened.sort();
+        assert_eq!(
+            opened,
+            vec![
+                vec![(2, "DD"), (7, "HH"), (11, "EE")],
+                vec![(3, "JJ"), (7, "BB"), (9, "CC")],
+            ]
+        );
+        assert!(plans.iter().all(|p| p.0.len() == 26));
+        assert_eq!(plans[0].released() + plans[1].released(), 1707);
     }
 }
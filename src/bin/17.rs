@@ -1,8 +1,10 @@
-
-use std::{
-    collections::{HashMap, HashSet},
-    fmt::Display,
+use advent_of_code::helpers::{
+    cycle,
+    image::{self, Image, Rgb},
+    viz::{Color, Frame, Player},
+    Direction, Point, Rect, SparseGrid,
 };
+use std::fmt::Display;
 
 pub fn part_one(input: &str) -> Option<isize> {
     Some(solve(input, 2022))
@@ -13,94 +15,131 @@ pub fn part_two(input: &str) -> Option<isize> {
 }
 
 fn solve(input: &str, years: usize) -> isize {
-    let rocks: Vec<Vec<Point>> = vec![
-        vec![[2, 0], [3, 0], [4, 0], [5, 0]],
-        vec![[2, 1], [3, 1], [3, 2], [3, 0], [4, 1]],
-        vec![[2, 0], [3, 0], [4, 0], [4, 1], [4, 2]],
-        vec![[2, 0], [2, 1], [2, 2], [2, 3]],
-        vec![[2, 0], [3, 0], [2, 1], [3, 1]],
-    ]
-    .iter()
-    .map(|r| r.iter().map(|p| (p[0], p[1])).collect::<Vec<_>>())
-    .collect();
+    let rocks = rocks();
+    let jets = parse(input);
 
-    // map of Cycle to (year, ymax)
-    let mut cycle_cache: HashMap<Cycle, (usize, isize)> = HashMap::new();
+    cycle::extrapolate(
+        Tower::default(),
+        years,
+        |tower| {
+            tower.drop_rock(&rocks, &jets);
+        },
+        |tower| CycleKey {
+            jet_index: (tower.jet % jets.len()) as u16,
+            shape_index: (tower.rock % rocks.len()) as u8,
+            y_offsets: tower.board.get_y_offsets(),
+        },
+        |tower| tower.board.get_max_y() + 1,
+    )
+    .value
+}
 
-    let jets: Vec<Jet> = input.trim().chars().map(|c| Jet::from(c)).collect();
+/// the top of the tower as each of the first 2022 rocks lands, with that rock highlighted
+fn frames(input: &str) -> impl Iterator<Item = Frame> {
+    let rocks = rocks();
+    let jets = parse(input);
+    let mut tower = Tower::default();
 
-    let mut board = Board::default();
-    let mut i = 0;
-    let mut cycle_found = false;
-    let mut cycled_y = 0;
+    (1..=2022).map(move |n| {
+        let landed = tower.drop_rock(&rocks, &jets);
+        let top = tower.board.get_max_y() + 1;
+        let rows = Rect::new(Point::new(0, (top - 30).max(0)), Point::new(6, top));
 
-    let mut year = 0;
-    while year < years {
-        let mut shape = Shape::new(rocks[year % 5].clone(), board.get_max_y() + 4);
+        // the text starts with the top row, one char in from the wall
+        let on_screen = |p: &Point| Point::new(p.x + 1, top - p.y);
+        Frame::new(tower.board.render(rows))
+            .highlight(landed.iter().map(on_screen), Color::Yellow)
+            .with_caption(format!("rock {}, height {}", n, top))
+    })
+}
 
-        loop {
-            let jet = jets[i % jets.len()];
-            shape.jet(&board, jet);
-            i += 1;
-            if shape.drop(&board) == DropResult::Done {
-                shape.settle(&mut board);
-                break;
+/// the top of the tower every 10 rocks, as pictures
+fn images(input: &str) -> Vec<Image> {
+    let rocks = rocks();
+    let jets = parse(input);
+    let mut tower = Tower::default();
+
+    (1..=2022)
+        .filter_map(|n| {
+            tower.drop_rock(&rocks, &jets);
+            if n % 10 != 0 {
+                return None;
             }
-        }
 
-        year += 1;
-
-        if !cycle_found {
-            let cycle = Cycle {
-                jet_index: (i % jets.len()) as u8,
-                shape_index: (year % 5) as u8,
-                y_offsets: board.get_y_offsets(),
-            };
-            if let Some((cycle_year, cycle_y)) = cycle_cache.get(&cycle) {
-                cycle_found = true;
-                let cycle_length = year - cycle_year;
-                let y_per_cycle = board.get_max_y() - cycle_y;
-                let cycles = (years - year) / cycle_length;
-                cycled_y = cycles as isize * y_per_cycle + 1;
-                let extra_years = cycles as usize * cycle_length;
-                year += extra_years;
-            } else {
-                cycle_cache.insert(cycle, (year, board.get_max_y()));
+            let top = tower.board.get_max_y() + 1;
+            let mut window = SparseGrid::new().with_flipped_y();
+            for y in top - 39..=top {
+                for x in 0..7 {
+                    let p = Point::new(x, y);
+                    window.insert(p, tower.board.contains(p) || y < 0);
+                }
             }
-        }
-    }
+            let image = window.to_image(|rock| match rock {
+                Some(&true) => Rgb::GRAY,
+                _ => Rgb::BLACK,
+            });
+            Some(image.scaled(8))
+        })
+        .collect()
+}
 
-    board.get_max_y() + cycled_y
+fn rocks() -> Vec<Vec<Point>> {
+    [
+        vec![[2, 0], [3, 0], [4, 0], [5, 0]],
+        vec![[2, 1], [3, 1], [3, 2], [3, 0], [4, 1]],
+        vec![[2, 0], [3, 0], [4, 0], [4, 1], [4, 2]],
+        vec![[2, 0], [2, 1], [2, 2], [2, 3]],
+        vec![[2, 0], [3, 0], [2, 1], [3, 1]],
+    ]
+    .iter()
+    .map(|r| r.iter().map(|p| Point::new(p[0], p[1])).collect::<Vec<_>>())
+    .collect()
+}
+
+fn parse(input: &str) -> Vec<Direction> {
+    input
+        .trim()
+        .chars()
+        .map(|c| Direction::try_from(c).expect("valid jet"))
+        .collect()
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 17);
+    if let Some(player) = Player::from_args() {
+        player.play(frames(input));
+    }
+    if let Some(path) = image::gif_path_from_args() {
+        image::save_gif(&images(input), 10, path).expect("could not save gif");
+    }
     advent_of_code::solve!(1, 17, part_one, input);
     advent_of_code::solve!(2, 17, part_two, input);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Jet {
-    Left,
-    Right,
-}
-
 #[derive(Debug, Clone)]
 struct Board {
-    board: HashSet<Point>,
-    max_y: isize,
+    board: SparseGrid<char>,
 }
 
 #[derive(Debug, Clone)]
 struct Shape {
     points: Vec<Point>,
-    x: isize,
-    y: isize,
+    /// where the shape's points are relative to
+    pos: Point,
+}
+
+/// how many rocks and jets have been used so far
+#[derive(Debug, Clone, Default)]
+struct Tower {
+    board: Board,
+    rock: usize,
+    jet: usize,
 }
 
+/// two towers with the same key will grow the same way from then on
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Cycle {
-    jet_index: u8,
+struct CycleKey {
+    jet_index: u16,
     shape_index: u8,
     /// the distance between y_max and the highest point in the column
     y_offsets: [u8; 7],
@@ -108,61 +147,92 @@ struct Cycle {
 
 impl Shape {
     fn new(points: Vec<Point>, y: isize) -> Self {
-        Self { points, x: 0, y }
-    }
-    fn jet(&mut self, board: &Board, jet: Jet) {
-        let mut new_points = Vec::new();
-        let x = match jet {
-            Jet::Left => self.x - 1,
-            Jet::Right => self.x + 1,
-        };
-        for p in &self.points {
-            let new_p = (p.0 + x, p.1 + self.y);
-            if board.board.contains(&new_p) || new_p.0 < 0 || new_p.0 > 6 {
+        Self {
+            points,
+            pos: Point::new(0, y),
+        }
+    }
+    fn jet(&mut self, board: &Board, jet: Direction) {
+        let pos = self.pos + jet;
+        for &p in &self.points {
+            let new_p = p + pos;
+            if board.contains(new_p) || new_p.x < 0 || new_p.x > 6 {
                 return;
             }
-            new_points.push(new_p);
         }
-        self.x = x;
+        self.pos = pos;
     }
     fn drop(&mut self, board: &Board) -> DropResult {
-        let mut new_points = Vec::new();
-        let y = self.y - 1;
-        for p in &self.points {
-            let new_p = (p.0 + self.x, p.1 + y);
-            if board.board.contains(&new_p) || new_p.1 < 0 {
+        // the tower grows upwards, so falling is towards smaller y
+        let pos = self.pos - Point::new(0, 1);
+        for &p in &self.points {
+            let new_p = p + pos;
+            if board.contains(new_p) || new_p.y < 0 {
                 return DropResult::Done;
             }
-            new_points.push(new_p);
         }
-        self.y -= 1;
+        self.pos = pos;
 
         DropResult::Continue
     }
-    fn settle(&self, board: &mut Board) {
-        for (x, y) in &self.points {
-            let p = (*x + self.x, *y + self.y);
+    fn settle(&self, board: &mut Board) -> Vec<Point> {
+        let points: Vec<_> = self.points.iter().map(|&p| p + self.pos).collect();
+        for &p in &points {
             board.insert(p);
         }
+        points
+    }
+}
+
+impl Tower {
+    /// drops the next rock and returns where it landed
+    fn drop_rock(&mut self, rocks: &[Vec<Point>], jets: &[Direction]) -> Vec<Point> {
+        let points = rocks[self.rock % rocks.len()].clone();
+        let mut shape = Shape::new(points, self.board.get_max_y() + 4);
+
+        loop {
+            shape.jet(&self.board, jets[self.jet % jets.len()]);
+            self.jet += 1;
+            if shape.drop(&self.board) == DropResult::Done {
+                self.rock += 1;
+                return shape.settle(&mut self.board);
+            }
+        }
     }
 }
 
 impl Board {
     fn get_max_y(&self) -> isize {
-        self.max_y
+        self.board.bounds().map_or(-1, |b| b.max.y)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.board.contains(point)
     }
 
     fn insert(&mut self, point: Point) {
-        self.max_y = self.max_y.max(point.1);
-        self.board.insert(point);
+        self.board.insert(point, '#');
+    }
+
+    /// the rows in `rect` between the walls, with the floor under them if it's in there
+    fn render(&self, rect: Rect) -> String {
+        let mut s = String::new();
+        for row in self.board.render(rect).lines() {
+            s.push_str(&format!("|{}|\n", row));
+        }
+        if rect.min.y == 0 {
+            s.push_str("+-------+");
+        }
+        s
     }
 
     fn get_y_offsets(&self) -> [u8; 7] {
+        let max_y = self.get_max_y();
         let mut y_offsets = [0; 7];
         for x in 0..7 {
-            for y in (0..=self.max_y).rev() {
-                if self.board.contains(&(x, y)) {
-                    y_offsets[x as usize] = (self.max_y - y) as u8;
+            for y in (0..=max_y).rev() {
+                if self.contains(Point { x, y }) {
+                    y_offsets[x as usize] = (max_y - y) as u8;
                     break;
                 }
             }
@@ -174,45 +244,18 @@ impl Board {
 impl Default for Board {
     fn default() -> Self {
         Self {
-            board: HashSet::new(),
-            max_y: -1,
-        }
-    }
-}
-
-impl From<char> for Jet {
-    fn from(c: char) -> Self {
-        match c {
-            '<' => Self::Left,
-            '>' => Self::Right,
-            _ => panic!("Invalid jet"),
+            board: SparseGrid::new().with_flipped_y(),
         }
     }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        for y in (0..(self.max_y + 2)).rev() {
-            s.push('|');
-            for x in 0..=6 {
-                if self.board.contains(&(x, y)) {
-                    s.push('#');
-                } else {
-                    s.push('.');
-                }
-            }
-            s.push('|');
-            s.push('\n');
-        }
-        s.push_str("+-------+");
-
-        write!(f, "{}", s)
+        let rect = Rect::new(Point::new(0, 0), Point::new(6, self.get_max_y() + 1));
+        write!(f, "{}", self.render(rect))
     }
 }
 
-type Point = (isize, isize);
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DropResult {
     Done,
@@ -234,4 +277,17 @@ mod tests {
         let input = advent_of_code::read_file("examples", 17);
         assert_eq!(part_two(&input), Some(1514285714288));
     }
+
+    #[test]
+    fn test_board_display() {
+        let mut board = Board::default();
+        for p in [(2, 0), (3, 0), (4, 0), (5, 0), (3, 1)] {
+            board.insert(p.into());
+        }
+
+        assert_eq!(
+            board.to_string(),
+            "|.......|\n|...#...|\n|..####.|\n+-------+"
+        );
+    }
 }
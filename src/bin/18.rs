@@ -1,82 +1,44 @@
-use std::collections::{BinaryHeap, HashSet};
+use advent_of_code::helpers::{search, Point3};
+use std::collections::HashSet;
 
 pub fn part_one(input: &str) -> Option<u32> {
     let points = parse(input);
 
-    let mut sum = 0;
-    for (x, y, z) in &points {
-        for (dx, dy, dz) in &OFFSETS {
-            if !points.contains(&(x + dx, y + dy, z + dz)) {
-                sum += 1;
-            }
-        }
-    }
+    let sum = points
+        .iter()
+        .flat_map(|p| p.faces())
+        .filter(|p| !points.contains(p))
+        .count();
 
-    Some(sum)
+    Some(sum as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    // start from min x,y,z (bc we know that's outside air). create a stack. check offsets for lava. if lava, add, otherwise add to stack.
+    // start from min x,y,z (bc we know that's outside air) and flood fill the air around the
+    // lava. every lava face touching that air is an outside face.
     let lava = parse(input);
-    let x = lava.iter().map(|(x, _, _)| x);
-    let y = lava.iter().map(|(_, y, _)| y);
-    let z = lava.iter().map(|(_, _, z)| z);
-    let minx = x.clone().min().unwrap() - 1;
-    let maxx = x.max().unwrap() + 1;
-    let miny = y.clone().min().unwrap() - 1;
-    let maxy = y.max().unwrap() + 1;
-    let minz = z.clone().min().unwrap() - 1;
-    let maxz = z.max().unwrap() + 1;
-
-    let mut heap: BinaryHeap<_> = vec![(minx, miny, minz)].into();
-    let mut visited = HashSet::new();
-
-    let mut sum = 0;
-
-    while let Some((x, y, z)) = heap.pop() {
-        if !visited.insert((x, y, z)) {
-            continue;
-        }
-
-        // out of bounds
-        if x < minx || x > maxx || y < miny || y > maxy || z < minz || z > maxz {
-            continue;
-        }
-
-        for (dx, dy, dz) in &OFFSETS {
-            let point = (x + dx, y + dy, z + dz);
-            if lava.contains(&point) {
-                sum += 1;
-            } else {
-                heap.push(point);
-            }
-        }
-    }
-
-    Some(sum)
+    let (min, max) = Point3::bounds(&lava).expect("some lava");
+    let min = min - Point3::splat(1);
+    let max = max + Point3::splat(1);
+
+    let air = search::reachable([min], |p| {
+        p.faces()
+            .filter(|p| p.is_within(&min, &max) && !lava.contains(p))
+    });
+
+    let sum = air
+        .iter()
+        .flat_map(|p| p.faces())
+        .filter(|p| lava.contains(p))
+        .count();
+
+    Some(sum as u32)
 }
-const OFFSETS: [Point; 6] = [
-    (1, 0, 0),
-    (0, 1, 0),
-    (0, 0, 1),
-    (-1, 0, 0),
-    (0, -1, 0),
-    (0, 0, -1),
-];
 
-type Point = (i8, i8, i8);
-
-fn parse(input: &str) -> HashSet<Point> {
+fn parse(input: &str) -> HashSet<Point3> {
     input
         .lines()
-        .map(|line| {
-            let nums = line
-                .split(",")
-                .map(|n| n.parse().unwrap())
-                .collect::<Vec<_>>();
-
-            (nums[0], nums[1], nums[2])
-        })
+        .map(|line| line.parse().expect("valid point"))
         .collect()
 }
 
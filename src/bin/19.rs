@@ -1,16 +1,20 @@
+use advent_of_code::helpers::{
+    optimize::{Optimizer, Problem, Solution},
+    parse,
+};
 use indicatif::ParallelProgressIterator;
 use rayon::prelude::*;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
+use std::fmt::Display;
 use std::ops::{Add, AddAssign, Index, IndexMut, Sub};
+use std::time::Instant;
 
 pub fn part_one(input: &str) -> Option<u32> {
     let blueprints = parse_to_blueprints(input, 24);
-    let answer = solve_blueprints(blueprints);
+    let answer = solve_blueprints(&blueprints);
     let answer = answer
         .iter()
-        .enumerate()
-        .map(|(idx, geode)| (idx.clone() as u32 + 1) * (*geode as u32))
+        .zip(blueprints)
+        .map(|(geode, blueprint)| blueprint.id * geode)
         .sum();
 
     Some(answer)
@@ -20,12 +24,12 @@ pub fn part_two(input: &str) -> Option<u32> {
     let mut blueprints = parse_to_blueprints(input, 32);
     blueprints.truncate(3);
 
-    let answer = solve_blueprints(blueprints);
+    let answer = solve_blueprints(&blueprints);
 
     Some(answer.into_iter().product())
 }
 
-fn solve_blueprints(blueprints: Vec<Blueprint>) -> Vec<u32> {
+fn solve_blueprints(blueprints: &[Blueprint]) -> Vec<u32> {
     let answer: Vec<_> = blueprints
         .par_iter()
         .progress_count(blueprints.len() as u64)
@@ -37,118 +41,196 @@ fn solve_blueprints(blueprints: Vec<Blueprint>) -> Vec<u32> {
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 19);
+    if pico_args::Arguments::from_env().contains("--explain") {
+        explain(input);
+    }
+
     advent_of_code::solve!(1, 19, part_one, input);
     advent_of_code::solve!(2, 19, part_two, input);
 }
 
-fn solve_blueprint(blueprint: Blueprint) -> u8 {
-    let max_robots = blueprint.max_robots();
-    let costs: Costs = blueprint.clone().into();
-    let first_state: State = blueprint.into();
-    let mut heap: BinaryHeap<_> = vec![first_state].into();
-    let mut set: HashSet<State> = HashSet::new();
-    let mut max_geode = u8::MIN;
-
-    while let Some(state) = heap.pop() {
-        // we've already calculated this branch
-        if set.contains(&state) {
-            continue;
-        }
-        match tick_state(&state, &max_robots, &costs) {
-            TickReturn::Loop(answers) => {
-                heap.extend(answers);
-            }
-            TickReturn::Answer(answer) => {
-                if answer > max_geode {
-                    max_geode = answer;
-                }
+/// prints what each blueprint builds over 24 and 32 minutes, then a table of how they did
+fn explain(input: &str) {
+    let blueprints = parse_to_blueprints(input, 24)
+        .into_iter()
+        .zip(parse_to_blueprints(input, 32));
+
+    let mut rows = Vec::new();
+    for (short, long) in blueprints {
+        let timer = Instant::now();
+        let (short_geodes, short_builds) = build_order(&short);
+        let (long_geodes, long_builds) = build_order(&long);
+        let elapsed = timer.elapsed();
+
+        for (blueprint, geodes, builds) in [
+            (&short, short_geodes, short_builds),
+            (&long, long_geodes, long_builds),
+        ] {
+            println!(
+                "Blueprint {}, {} minutes: {} geodes",
+                blueprint.id, blueprint.time_remaining, geodes
+            );
+            for (minute, robot) in builds {
+                println!("  minute {:>2}: {}", minute, robot);
             }
         }
-        set.insert(state);
+        rows.push((long.id, short_geodes, long_geodes, elapsed));
     }
 
-    max_geode
+    println!();
+    println!("blueprint | 24 minutes | 32 minutes | time");
+    for (id, short, long, elapsed) in rows {
+        println!("{:>9} | {:>10} | {:>10} | {:.2?}", id, short, long, elapsed);
+    }
+    println!();
 }
 
-fn tick_state(state: &State, max_robots: &MaxRobots, costs: &Costs) -> TickReturn {
-    // if this is the last cycle, building robots won't make a difference, so just return early
-    if state.time_remaining == 1 {
-        let mut new = state.clone();
-        new.tick();
-        return TickReturn::Answer(new.inventory[Geode]);
-    }
+fn solve_blueprint(blueprint: Blueprint) -> u16 {
+    best_builds(&blueprint).score as u16
+}
+
+/// the most geodes the blueprint can crack, and the robots to build for them along with the
+/// minute each one gets started
+fn build_order(blueprint: &Blueprint) -> (u16, Vec<(u8, Robots)>) {
+    let minutes = blueprint.time_remaining as u8;
+    let costs: Costs = blueprint.clone().into();
+    let solution = best_builds(blueprint);
+
+    let mut state: State = blueprint.clone().into();
+    let builds = solution
+        .decisions
+        .into_iter()
+        .map(|robot| {
+            let wait = state
+                .wait_for(&costs[robot])
+                .expect("only affordable robots get built");
+            for _ in 0..wait {
+                state.tick();
+            }
+            let minute = minutes - state.time_remaining + 1;
+            state = state.w_new_robot(&costs, robot);
+            (minute, robot)
+        })
+        .collect();
 
-    let mut answers = Vec::new();
+    (solution.score as u16, builds)
+}
 
-    if state.inventory > costs[Geode] {
-        answers.push(state.w_new_robot(costs, Geode));
+fn best_builds(blueprint: &Blueprint) -> Solution<Robots> {
+    let max_robots = blueprint.max_robots();
+    let costs: Costs = blueprint.clone().into();
+    let factory = Factory {
+        costs: &costs,
+        max_robots: &max_robots,
+    };
+
+    Optimizer::new(&factory).solve(blueprint.clone().into())
+}
+
+/// choosing which robot to build next. waiting is never a decision of its own, the factory just
+/// skips ahead to when it can afford the next robot
+struct Factory<'a> {
+    costs: &'a Costs,
+    max_robots: &'a MaxRobots,
+}
+
+impl Problem for Factory<'_> {
+    type State = State;
+    type Decision = Robots;
+    type Key = ();
+
+    fn successors(&self, state: &State) -> Vec<(Robots, State)> {
+        [Geode, Obsidian, Clay, Ore]
+            .into_iter()
+            .filter(|&robot| state.robots[robot] < self.max_robots[robot])
+            .filter_map(|robot| {
+                let wait = state.wait_for(&self.costs[robot])?;
+                // a robot finished in the last minute never gets to collect anything
+                if wait + 1 >= state.time_remaining {
+                    return None;
+                }
 
-        // if we can build a geode, we always should
-        return TickReturn::Loop(answers);
-    }
-    if state.inventory > costs[Ore] && state.robots[Ore] < max_robots[Ore] {
-        answers.push(state.w_new_robot(costs, Ore));
-    }
-    if state.inventory > costs[Clay] && state.robots[Clay] < max_robots[Clay] {
-        answers.push(state.w_new_robot(costs, Clay));
+                let mut next = state.clone();
+                for _ in 0..wait {
+                    next.tick();
+                }
+                Some((robot, next.w_new_robot(self.costs, robot)))
+            })
+            .collect()
     }
-    if state.inventory > costs[Obsidian] && state.robots[Obsidian] < max_robots[Obsidian] {
-        answers.push(state.w_new_robot(costs, Obsidian));
+
+    fn score(&self, state: &State) -> u64 {
+        // the geode robots keep cracking until the end
+        (state.inventory[Geode] + state.robots[Geode] * state.time_remaining as u16) as u64
     }
 
-    // no change
-    let mut new = state.clone();
-    new.tick();
-    answers.push(new);
+    fn bound(&self, state: &State) -> u64 {
+        // as if ore and clay were free, so a new obsidian robot comes every minute, and a geode
+        // robot too whenever there's enough obsidian
+        let geode_cost = self.costs[Geode][Obsidian];
+        let mut obsidian = state.inventory[Obsidian];
+        let mut geodes = state.inventory[Geode];
+        let mut geode_robots = state.robots[Geode];
+
+        let obsidian_robots = state.robots[Obsidian]..;
+        for obsidian_robots in obsidian_robots.take(state.time_remaining as usize) {
+            let build = obsidian >= geode_cost;
+            if build {
+                obsidian -= geode_cost;
+            }
+            obsidian += obsidian_robots;
+            geodes += geode_robots;
+            if build {
+                geode_robots += 1;
+            }
+        }
 
-    TickReturn::Loop(answers)
+        geodes as u64
+    }
 }
 
 fn parse_to_blueprints(input: &str, time_remaining: u32) -> Vec<Blueprint> {
-    let blueprints: Vec<Vec<&str>> = input
-        .split("\n")
-        .map(|i| i.split_whitespace().collect())
-        .collect();
-
-    let blueprints: Vec<&Vec<_>> = blueprints.iter().filter(|i| i.len() > 0).collect();
-
-    let blueprints: Vec<Blueprint> = blueprints
-        .iter()
-        .map(|b| Blueprint {
-            ore_r_count: 1,
-            ore_cost: Cost {
-                ore: b[6].parse().unwrap(),
-                ..Default::default()
-            },
-            clay_cost: Cost {
-                ore: b[12].parse().unwrap(),
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            // Blueprint {id}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each
+            // obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.
+            let [id, ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian] =
+                parse::integers(line)[..]
+            else {
+                panic!("invalid blueprint: {}", line);
+            };
+
+            Blueprint {
+                id,
+                ore_r_count: 1,
+                ore_cost: Cost {
+                    ore,
+                    ..Default::default()
+                },
+                clay_cost: Cost {
+                    ore: clay,
+                    ..Default::default()
+                },
+                obsidian_cost: Cost {
+                    ore: obsidian_ore,
+                    clay: obsidian_clay,
+                    ..Default::default()
+                },
+                geode_cost: Cost {
+                    ore: geode_ore,
+                    obsidian: geode_obsidian,
+                    ..Default::default()
+                },
+                time_remaining,
                 ..Default::default()
-            },
-            obsidian_cost: Cost {
-                ore: b[18].parse().unwrap(),
-                clay: b[21].parse().unwrap(),
-                ..Default::default()
-            },
-            geode_cost: Cost {
-                ore: b[27].parse().unwrap(),
-                obsidian: b[30].parse().unwrap(),
-                ..Default::default()
-            },
-            time_remaining,
-            ..Default::default()
+            }
         })
-        .collect();
-
-    blueprints
-}
-
-#[derive(Debug, Clone)]
-enum TickReturn {
-    Loop(Vec<State>),
-    Answer(u8),
+        .collect()
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Robots {
     Ore,
     Clay,
@@ -157,8 +239,20 @@ enum Robots {
 }
 use Robots::*;
 
+impl Display for Robots {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let robot = match self {
+            Ore => "ore robot",
+            Clay => "clay robot",
+            Obsidian => "obsidian robot",
+            Geode => "geode robot",
+        };
+        write!(f, "{}", robot)
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Resources(u8, u8, u8, u8);
+struct Resources(u16, u16, u16, u16);
 
 struct Costs([Resources; 4]);
 
@@ -170,7 +264,7 @@ struct State {
 }
 
 #[derive(Default, Debug, Hash, PartialEq, Eq)]
-struct MaxRobots(u8, u8, u8);
+struct MaxRobots(u16, u16, u16);
 
 #[derive(Default, Debug, Clone, Hash, PartialEq, Eq, Copy)]
 struct Cost {
@@ -194,10 +288,10 @@ impl From<Blueprint> for Costs {
 impl From<Cost> for Resources {
     fn from(cost: Cost) -> Self {
         Resources(
-            cost.ore as u8,
-            cost.clay as u8,
-            cost.obsidian as u8,
-            cost.geode as u8,
+            cost.ore as u16,
+            cost.clay as u16,
+            cost.obsidian as u16,
+            cost.geode as u16,
         )
     }
 }
@@ -206,42 +300,42 @@ impl From<Blueprint> for State {
     fn from(blueprint: Blueprint) -> Self {
         State {
             inventory: Default::default(),
-            robots: Resources(blueprint.ore_r_count as u8, 0, 0, 0),
+            robots: Resources(blueprint.ore_r_count as u16, 0, 0, 0),
             time_remaining: blueprint.time_remaining as u8,
         }
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.priority().cmp(&other.priority())
-    }
-}
-
 impl State {
     fn tick(&mut self) {
         self.time_remaining -= 1;
         self.inventory += self.robots;
     }
-    fn priority(&self) -> isize {
-        // this is so ugly LMAO
-        let ans = self.inventory.geode() * 100
-            + self.robots.geode() * 500
-            + self.inventory.obsidian() * 10
-            + self.robots.obsidian() * 50;
 
-        (ans as isize) - self.time_remaining as isize * 15 - self.inventory.clay() as isize * 2
+    /// how many minutes until `cost` is affordable, or `None` if it never will be with the
+    /// robots there are now
+    fn wait_for(&self, cost: &Resources) -> Option<u8> {
+        if self.inventory.can_afford(cost) {
+            return Some(0);
+        }
+
+        [Ore, Clay, Obsidian, Geode]
+            .into_iter()
+            .filter(|&r| cost[r] > self.inventory[r])
+            .map(|r| {
+                let missing = cost[r] - self.inventory[r];
+                match self.robots[r] {
+                    0 => None,
+                    robots => Some(missing.div_ceil(robots) as u8),
+                }
+            })
+            .try_fold(0, |wait, w| Some(wait.max(w?)))
     }
 
     fn w_new_robot(&self, costs: &Costs, robot: Robots) -> State {
+        debug_assert!(self.inventory.can_afford(&costs[robot]));
         let mut state = self.clone();
-        state.inventory = state.inventory - costs[robot].clone();
+        state.inventory = state.inventory - costs[robot];
         state.tick();
         state.robots.inc(robot, 1);
 
@@ -282,7 +376,7 @@ impl AddAssign for Resources {
 }
 
 impl Index<Robots> for Resources {
-    type Output = u8;
+    type Output = u16;
     fn index(&self, index: Robots) -> &Self::Output {
         match index {
             Robots::Ore => &self.0,
@@ -304,42 +398,19 @@ impl IndexMut<Robots> for Resources {
     }
 }
 
-impl PartialOrd for Resources {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Resources {
+    /// whether there's at least as much of every resource as `cost` needs
+    fn can_afford(&self, cost: &Resources) -> bool {
+        self.dominates(cost)
     }
-}
 
-impl Ord for Resources {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.0 < other.0 {
-            return Ordering::Less;
-        }
-        if self.1 < other.1 {
-            return Ordering::Less;
-        }
-        if self.2 < other.2 {
-            return Ordering::Less;
-        }
-        if self.3 < other.3 {
-            return Ordering::Less;
-        }
-
-        Ordering::Greater
+    /// whether there's at least as much of every resource as in `other`. two amounts can both
+    /// have more of something, in which case neither dominates
+    fn dominates(&self, other: &Resources) -> bool {
+        self.0 >= other.0 && self.1 >= other.1 && self.2 >= other.2 && self.3 >= other.3
     }
-}
 
-impl Resources {
-    fn clay(&self) -> usize {
-        self.1 as usize
-    }
-    fn obsidian(&self) -> usize {
-        self.2 as usize
-    }
-    fn geode(&self) -> usize {
-        self.3 as usize
-    }
-    fn inc(&mut self, robot: Robots, amount: u8) {
+    fn inc(&mut self, robot: Robots, amount: u16) {
         self[robot] += amount;
     }
 }
@@ -357,19 +428,20 @@ impl Index<Robots> for Costs {
 }
 
 impl Index<Robots> for MaxRobots {
-    type Output = u8;
+    type Output = u16;
     fn index(&self, index: Robots) -> &Self::Output {
         match index {
             Robots::Ore => &self.0,
             Robots::Clay => &self.1,
             Robots::Obsidian => &self.2,
-            Robots::Geode => &u8::MAX,
+            Robots::Geode => &u16::MAX,
         }
     }
 }
 
-#[derive(Default, Debug, Clone, Hash, PartialEq, Eq, PartialOrd)]
+#[derive(Default, Debug, Clone, Hash, PartialEq, Eq)]
 struct Blueprint {
+    id: u32,
     ore_r_count: u32,
     ore_cost: Cost,
     obsidian_cost: Cost,
@@ -392,34 +464,17 @@ impl Sub for Cost {
     }
 }
 
-impl PartialOrd for Cost {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Cost {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.ore < other.ore {
-            return Ordering::Less;
-        }
-        if self.clay < other.clay {
-            return Ordering::Less;
-        }
-        if self.obsidian < other.obsidian {
-            return Ordering::Less;
-        }
-        if self.geode < other.geode {
-            return Ordering::Less;
-        }
-
-        Ordering::Greater
-    }
-}
-
 impl Blueprint {
     fn max_ore_robots(&self) -> u32 {
-        self.geode_cost.ore.max(self.clay_cost.ore)
+        [
+            self.ore_cost.ore,
+            self.clay_cost.ore,
+            self.obsidian_cost.ore,
+            self.geode_cost.ore,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
     }
     fn max_clay_robots(&self) -> u32 {
         self.geode_cost.clay.max(self.obsidian_cost.clay)
@@ -430,9 +485,9 @@ impl Blueprint {
 
     fn max_robots(&self) -> MaxRobots {
         MaxRobots(
-            self.max_ore_robots() as u8,
-            self.max_clay_robots() as u8,
-            self.max_obsidian_robots() as u8,
+            self.max_ore_robots() as u16,
+            self.max_clay_robots() as u16,
+            self.max_obsidian_robots() as u16,
         )
     }
 }
@@ -452,4 +507,65 @@ mod tests {
         let input = advent_of_code::read_file("examples", 19);
         assert_eq!(part_two(&input), Some(3472));
     }
+
+    #[test]
+    fn test_build_order() {
+        let input = advent_of_code::read_file("examples", 19);
+        let blueprints = parse_to_blueprints(&input, 24);
+
+        let (geodes, builds) = build_order(&blueprints[0]);
+        assert_eq!(geodes, 9);
+
+        // replaying the builds cracks as many geodes as promised
+        let costs: Costs = blueprints[0].clone().into();
+        let mut state: State = blueprints[0].clone().into();
+        for (minute, robot) in &builds {
+            while 24 - state.time_remaining + 1 < *minute {
+                state.tick();
+            }
+            assert!(state.inventory.can_afford(&costs[*robot]));
+            state = state.w_new_robot(&costs, *robot);
+        }
+        while state.time_remaining > 0 {
+            state.tick();
+        }
+        assert_eq!(state.inventory[Geode], 9);
+
+        let geode_robots = builds.iter().filter(|(_, r)| *r == Geode).count();
+        assert_eq!(geode_robots, 2);
+    }
+
+    #[test]
+    fn test_resources() {
+        let cost = Resources(2, 0, 7, 0);
+
+        // equal amounts dominate each other, and exactly enough is enough
+        assert!(cost.dominates(&cost));
+        assert!(cost.can_afford(&cost));
+
+        let more = Resources(3, 1, 7, 0);
+        assert!(more.can_afford(&cost));
+        assert!(!cost.can_afford(&more));
+
+        // more ore but less obsidian, so neither is better
+        let incomparable = Resources(5, 0, 6, 0);
+        assert!(!incomparable.dominates(&cost));
+        assert!(!cost.dominates(&incomparable));
+        assert!(!incomparable.can_afford(&cost));
+    }
+
+    #[test]
+    fn test_wait_for() {
+        let state = State {
+            inventory: Resources(2, 0, 6, 0),
+            robots: Resources(1, 0, 1, 0),
+            time_remaining: 10,
+        };
+
+        assert_eq!(state.wait_for(&Resources(2, 0, 6, 0)), Some(0));
+        assert_eq!(state.wait_for(&Resources(2, 0, 7, 0)), Some(1));
+        assert_eq!(state.wait_for(&Resources(4, 0, 6, 0)), Some(2));
+        // there are no clay robots to ever collect it
+        assert_eq!(state.wait_for(&Resources(1, 1, 0, 0)), None);
+    }
 }
@@ -1,80 +1,68 @@
+use advent_of_code::helpers::mixer::Decryption;
 use indicatif::ProgressBar;
+use std::process;
 
 pub fn part_one(input: &str) -> Option<isize> {
-    let mut nums = parse(input, 1);
-
-    mix(&mut nums, 1);
-
-    Some(gps(&nums))
+    decrypt(input, &Decryption::part_one())
 }
 
 pub fn part_two(input: &str) -> Option<isize> {
-    let mut nums = parse(input, 811589153);
-    mix(&mut nums, 10);
-
-    Some(gps(&nums))
+    decrypt(input, &Decryption::part_two())
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 20);
-    advent_of_code::solve!(1, 20, part_one, input);
-    advent_of_code::solve!(2, 20, part_two, input);
-}
-
-#[derive(Clone, Copy)]
-struct Num {
-    /// the value of the actual number when parsed
-    value: isize,
-    /// the index this was when parsed
-    index: usize,
-}
-
-fn mix(nums: &mut Vec<Num>, rounds: usize) {
-    let bar = ProgressBar::new(rounds as u64 * nums.len() as u64);
-    for _ in 0..rounds {
-        for i in 0..nums.len() {
-            let index = nums.iter().position(|n| n.index == i).unwrap();
-            let num = nums.remove(index);
-            let mut new_index = index as isize + num.value;
-            if new_index < 0 {
-                new_index += (nums.len() * (new_index.abs() as usize / nums.len() + 1)) as isize;
-            }
-
-            new_index %= nums.len() as isize;
-            nums.insert(new_index as usize, num);
-
-            bar.inc(1);
-
-            // print_nums(&nums);
+    if let Some(decryption) = decryption_from_args() {
+        match decrypt(input, &decryption) {
+            Some(sum) => println!("With {}: {}", decryption, sum),
+            None => println!("With {}: there's no 0 to count from", decryption),
         }
     }
+
+    advent_of_code::solve!(1, 20, part_one, input);
+    advent_of_code::solve!(2, 20, part_two, input);
 }
 
-fn gps(nums: &Vec<Num>) -> isize {
-    let zero_val = nums.iter().position(|n| n.value == 0).unwrap();
+/// the sum of the grove coordinates, or `None` if there's no 0 to count them from
+fn decrypt(input: &str, decryption: &Decryption) -> Option<isize> {
+    let values = parse(input);
+    let bar = ProgressBar::new(decryption.rounds as u64 * values.len() as u64);
 
-    (1000..=3000)
-        .filter(|i| i % 1000 == 0)
-        .map(|i| nums[(i + zero_val) % nums.len()].value)
-        .sum()
+    decryption.decrypt(&values, || bar.inc(1))
 }
 
-fn print_nums(nums: &Vec<Num>) {
-    for num in nums {
-        print!("{},", num.value);
+/// part two's decryption with whatever `--key`, `--rounds` and `--offsets 1000,2000,3000` were
+/// passed swapped in, or `None` if none of them were
+fn decryption_from_args() -> Option<Decryption> {
+    let mut args = pico_args::Arguments::from_env();
+    let exit = |e: pico_args::Error| -> ! {
+        eprintln!("Failed to process arguments: {}", e);
+        process::exit(1);
+    };
+
+    let key = args.opt_value_from_str("--key").unwrap_or_else(|e| exit(e));
+    let rounds = args
+        .opt_value_from_str("--rounds")
+        .unwrap_or_else(|e| exit(e));
+    let offsets = args
+        .opt_value_from_fn("--offsets", |s| {
+            s.split(',').map(str::parse).collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_else(|e| exit(e));
+    if key.is_none() && rounds.is_none() && offsets.is_none() {
+        return None;
     }
-    println!();
+
+    let default = Decryption::part_two();
+    Some(Decryption {
+        key: key.unwrap_or(default.key),
+        rounds: rounds.unwrap_or(default.rounds),
+        offsets: offsets.unwrap_or(default.offsets),
+    })
 }
 
-fn parse(input: &str, key: isize) -> Vec<Num> {
-    input
-        .lines()
-        .enumerate()
-        .map(|(index, line)| Num {
-            value: line.parse::<isize>().unwrap() * key,
-            index,
-        })
-        .collect()
+fn parse(input: &str) -> Vec<isize> {
+    input.lines().map(|line| line.parse().unwrap()).collect()
 }
 
 #[cfg(test)]
@@ -92,4 +80,16 @@ mod tests {
         let input = advent_of_code::read_file("examples", 20);
         assert_eq!(part_two(&input), Some(1623178306));
     }
+
+    #[test]
+    fn test_decrypt() {
+        let input = advent_of_code::read_file("examples", 20);
+        let decryption = Decryption {
+            key: 2,
+            rounds: 0,
+            offsets: vec![1],
+        };
+        assert_eq!(decrypt(&input, &decryption), Some(8));
+        assert_eq!(decrypt("7", &Decryption::part_one()), None);
+    }
 }
@@ -1,85 +1,175 @@
-use std::collections::HashMap;
+use advent_of_code::helpers::{
+    parse::{self, ParseError},
+    Graph, Interner, Rational,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alpha1, one_of},
+    combinator::map,
+    sequence::{separated_pair, tuple},
+    IResult,
+};
+use std::fmt::Display;
 
 pub fn part_one(input: &str) -> Option<i64> {
-    let monkeys = parse(input);
-    let root = monkeys.get("root").expect("root monkey");
+    let monkeys = parse(input).unwrap_or_else(|e| panic!("{}", e));
+    // `None` once a monkey divides by 0 or overflows, and for everyone listening to it
+    let values = monkeys.evaluate(
+        |_, number| Some(number),
+        |operator, left, right| {
+            let (left, right) = ((*left)?, (*right)?);
+            match operator {
+                Operator::Add => left.checked_add(right),
+                Operator::Multiply => left.checked_mul(right),
+                Operator::Divide => left.checked_div(right),
+                Operator::Subtract => left.checked_sub(right),
+            }
+        },
+    );
 
-    Some(root.calc(&monkeys))
+    values[monkeys.root]
 }
 
 pub fn part_two(input: &str) -> Option<i64> {
-    // plan of attack: find which side of a calc depends on humn. we then know what the other side has
-    // to be, and can pass that value into the function recursively
-
-    let map = parse(input);
-    let root = map.get("root").expect("root monkey");
-    let (_, left_name, right_name) = match root {
-        Monkey::Number(_) => panic!("root is a number"),
-        Monkey::Calculated(op, left, right) => (op, left, right),
-    };
-
-    let left = map.get(left_name).unwrap();
-    let right = map.get(right_name).unwrap();
-
-    let (goal, monkey) = if left.depends_on_humn(&map) {
-        (right.calc(&map), left_name)
-    } else {
-        (left.calc(&map), right_name)
-    };
-
-    let humn_value = calc_humn(goal, monkey, &map);
-
-    Some(humn_value)
-}
-
-fn calc_humn(value: i64, name: &str, map: &HashMap<&str, Monkey>) -> i64 {
-    // if we have found humn, we return the value that we expect humn to be
-    if name == "humn" {
-        return value;
-    }
-
-    let monkey = map.get(name).unwrap();
-    match monkey {
-        Monkey::Number(number) => *number,
-        Monkey::Calculated(op, ls, rs) => {
-            let left = map.get(ls).unwrap();
-            let right = map.get(rs).unwrap();
-
-            let (new_name, new_goal) = if left.depends_on_humn(map) || ls == &"humn" {
-                let right_val = right.calc(map);
-                // now we switch the operator. we know what we want this to equal, and the right
-                // hand side, so with that we can figure out what the left side of the monkey has
-                // to equal
-                let new_goal = match op {
-                    Operator::Add => value - right_val,
-                    Operator::Multiply => value / right_val,
-                    Operator::Divide => value * right_val,
-                    Operator::Subtract => value + right_val,
-                };
-                (ls, new_goal)
-            } else {
-                let left_val = left.calc(map);
-                let new_goal = match op {
-                    Operator::Add => value - left_val,
-                    Operator::Multiply => value / left_val,
-                    Operator::Divide => value * left_val,
-                    Operator::Subtract => left_val - value,
+    let monkeys = parse(input).unwrap_or_else(|e| panic!("{}", e));
+    let equation = monkeys.equation();
+
+    match equation.solve() {
+        Ok(humn) => Some(humn),
+        Err(e) => panic!("can't solve {}: {}", equation, e),
+    }
+}
+
+/// a monkey's number in terms of what you yell, with everything that doesn't depend on you
+/// already worked out
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(Rational),
+    Humn,
+    Calculated(Operator, Box<Expr>, Box<Expr>),
+}
+
+/// `root`'s two numbers have to be equal
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Equation {
+    left: Expr,
+    right: Expr,
+}
+
+/// `a * humn + b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Linear {
+    a: Rational,
+    b: Rational,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SolveError {
+    /// `humn` gets multiplied by itself or divided into something
+    NonLinear,
+    DivisionByZero,
+    /// both sides are the same whatever you yell
+    AnyValue,
+    /// both sides differ whatever you yell
+    NoValue,
+    /// the only answer isn't a whole number
+    NotInteger(Rational),
+    /// the fractions on the way got too big for an `i128`
+    Overflow,
+}
+
+impl Expr {
+    /// combines the two, working it out straight away if neither depends on `humn`
+    fn calculated(operator: Operator, left: Expr, right: Expr) -> Expr {
+        if let (Expr::Number(l), Expr::Number(r)) = (&left, &right) {
+            if let Some(n) = operator.apply(*l, *r) {
+                return Expr::Number(n);
+            }
+        }
+        Expr::Calculated(operator, Box::new(left), Box::new(right))
+    }
+
+    fn linear(&self) -> Result<Linear, SolveError> {
+        let constant = |b| Linear {
+            a: Rational::ZERO,
+            b,
+        };
+
+        Ok(match self {
+            Expr::Number(n) => constant(*n),
+            Expr::Humn => Linear {
+                a: Rational::ONE,
+                b: Rational::ZERO,
+            },
+            Expr::Calculated(operator, left, right) => {
+                let (l, r) = (left.linear()?, right.linear()?);
+                let checked = |a: Option<Rational>, b: Option<Rational>| match (a, b) {
+                    (Some(a), Some(b)) => Ok(Linear { a, b }),
+                    _ => Err(SolveError::Overflow),
                 };
-                (rs, new_goal)
-            };
+                match operator {
+                    Operator::Add => checked(l.a.checked_add(r.a), l.b.checked_add(r.b))?,
+                    Operator::Subtract => checked(l.a.checked_sub(r.a), l.b.checked_sub(r.b))?,
+                    Operator::Multiply if l.a.is_zero() => {
+                        checked(l.b.checked_mul(r.a), l.b.checked_mul(r.b))?
+                    }
+                    Operator::Multiply if r.a.is_zero() => {
+                        checked(l.a.checked_mul(r.b), l.b.checked_mul(r.b))?
+                    }
+                    Operator::Multiply => return Err(SolveError::NonLinear),
+                    Operator::Divide if !r.a.is_zero() => return Err(SolveError::NonLinear),
+                    Operator::Divide if r.b.is_zero() => return Err(SolveError::DivisionByZero),
+                    Operator::Divide => checked(l.a.checked_div(r.b), l.b.checked_div(r.b))?,
+                }
+            }
+        })
+    }
+}
 
-            calc_humn(new_goal, new_name, map)
+impl Equation {
+    /// what you have to yell for both sides to match
+    fn solve(&self) -> Result<i64, SolveError> {
+        // a * humn + b = 0
+        let (left, right) = (self.left.linear()?, self.right.linear()?);
+        let overflow = |n: Option<Rational>| n.ok_or(SolveError::Overflow);
+        let a = overflow(left.a.checked_sub(right.a))?;
+        let b = overflow(right.b.checked_sub(left.b))?;
+
+        if a.is_zero() {
+            return Err(if b.is_zero() {
+                SolveError::AnyValue
+            } else {
+                SolveError::NoValue
+            });
         }
+
+        let humn = overflow(b.checked_div(a))?;
+        humn.to_integer()
+            .and_then(|humn| i64::try_from(humn).ok())
+            .ok_or(SolveError::NotInteger(humn))
     }
 }
 
+/// the monkeys, checked to all be defined once and to not depend on each other in a loop
+struct Monkeys {
+    /// in the order they're defined
+    monkeys: Vec<Monkey>,
+    /// every monkey after the ones it listens to
+    order: Vec<usize>,
+    root: usize,
+    humn: usize,
+    /// whether what you yell changes each monkey's number
+    depends_on_humn: Vec<bool>,
+}
+
 #[derive(Debug)]
-enum Monkey<'a> {
+enum Monkey {
     Number(i64),
-    Calculated(Operator, &'a str, &'a str),
+    Calculated(Operator, usize, usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operator {
     Add,
     Multiply,
@@ -89,84 +179,256 @@ enum Operator {
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 21);
+    if pico_args::Arguments::from_env().contains("--explain") {
+        match parse(input) {
+            Ok(monkeys) => println!("{}\n", monkeys.equation()),
+            Err(e) => println!("{}\n", e),
+        }
+    }
+
     advent_of_code::solve!(1, 21, part_one, input);
     advent_of_code::solve!(2, 21, part_two, input);
 }
 
-impl Monkey<'_> {
-    fn calc(&self, map: &HashMap<&str, Monkey>) -> i64 {
-        let ans = match self {
-            Monkey::Number(number) => *number,
-            Monkey::Calculated(operator, left, right) => {
-                let left = map.get(left).unwrap().calc(map);
-                let right = map.get(right).unwrap().calc(map);
-                match operator {
-                    Operator::Add => left + right,
-                    Operator::Multiply => left * right,
-                    Operator::Divide => left / right,
-                    Operator::Subtract => left - right,
+impl Monkeys {
+    /// works out a `T` for every monkey from the ones it listens to, in topological order
+    fn evaluate<T: Clone>(
+        &self,
+        number: impl Fn(usize, i64) -> T,
+        calculated: impl Fn(Operator, &T, &T) -> T,
+    ) -> Vec<T> {
+        let mut values: Vec<Option<T>> = vec![None; self.monkeys.len()];
+        for &id in &self.order {
+            let value = match &self.monkeys[id] {
+                Monkey::Number(n) => number(id, *n),
+                Monkey::Calculated(operator, left, right) => {
+                    let (Some(left), Some(right)) = (&values[*left], &values[*right]) else {
+                        unreachable!("monkeys come after the ones they listen to");
+                    };
+                    calculated(*operator, left, right)
                 }
+            };
+            values[id] = Some(value);
+        }
+
+        values.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// what `root` compares, as expressions in `humn`. the side with `humn` in it goes on the
+    /// left if only one of them has it
+    fn equation(&self) -> Equation {
+        let Monkey::Calculated(_, left, right) = self.monkeys[self.root] else {
+            unreachable!("parse checks that root compares two monkeys");
+        };
+        let (left, right) = if self.depends_on_humn[right] && !self.depends_on_humn[left] {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        let exprs = self.evaluate(
+            |id, n| match id == self.humn {
+                true => Expr::Humn,
+                false => Expr::Number(n.into()),
+            },
+            |operator, left, right| Expr::calculated(operator, left.clone(), right.clone()),
+        );
+        Equation {
+            left: exprs[left].clone(),
+            right: exprs[right].clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MonkeyError {
+    Parse(ParseError),
+    Duplicate(String),
+    Unknown {
+        monkey: String,
+        name: String,
+    },
+    /// the monkeys in a loop of listening to each other
+    Cycle(Vec<String>),
+    Missing(&'static str),
+    /// `root` yells a number, so there's nothing to compare
+    RootNumber,
+    /// `humn` works its number out, so it isn't yours to yell
+    HumnCalculated,
+}
+
+/// a monkey's job before the names it listens to are looked up
+enum Job<'a> {
+    Number(i64),
+    Calculated(Operator, &'a str, &'a str),
+}
+
+fn parse(input: &str) -> Result<Monkeys, MonkeyError> {
+    let lines = parse::lines(input, monkey_line).map_err(MonkeyError::Parse)?;
+
+    // every name gets its id from the line it's defined on, so ids line up with `monkeys`
+    let mut names = Interner::new();
+    for (name, _) in &lines {
+        if names.get(name).is_some() {
+            return Err(MonkeyError::Duplicate(name.to_string()));
+        }
+        names.intern(name);
+    }
+
+    let mut monkeys = Vec::new();
+    // an edge from each monkey to the ones that use its number
+    let mut graph = Graph::new(names.len());
+    for (id, (name, job)) in lines.into_iter().enumerate() {
+        let lookup = |other: &str| {
+            names.get(other).ok_or_else(|| MonkeyError::Unknown {
+                monkey: name.to_string(),
+                name: other.to_string(),
+            })
+        };
+
+        let monkey = match job {
+            Job::Number(n) => Monkey::Number(n),
+            Job::Calculated(operator, left, right) => {
+                let (left, right) = (lookup(left)?, lookup(right)?);
+                graph.add_edge(left, id);
+                graph.add_edge(right, id);
+                Monkey::Calculated(operator, left, right)
             }
         };
+        monkeys.push(monkey);
+    }
 
-        ans
+    let order = graph
+        .topological_sort()
+        .map_err(|e| MonkeyError::Cycle(e.0.iter().map(|&id| names[id].to_string()).collect()))?;
+    let root = names.get("root").ok_or(MonkeyError::Missing("root"))?;
+    let humn = names.get("humn").ok_or(MonkeyError::Missing("humn"))?;
+    if let Monkey::Number(_) = monkeys[root] {
+        return Err(MonkeyError::RootNumber);
+    }
+    if let Monkey::Calculated(..) = monkeys[humn] {
+        return Err(MonkeyError::HumnCalculated);
     }
 
-    fn depends_on_humn(&self, map: &HashMap<&str, Monkey>) -> bool {
-        match self {
-            // in a perfect world you could check if this number monkey is humn, but my data
-            // modelling was bad so it's not possible...
-            Monkey::Number(_) => false,
-            Monkey::Calculated(_, left, right) => {
-                if left == &"humn" || right == &"humn" {
-                    true
-                } else {
-                    let left = map.get(left).unwrap();
-                    let right = map.get(right).unwrap();
-                    left.depends_on_humn(map) || right.depends_on_humn(map)
+    let mut depends_on_humn = vec![false; monkeys.len()];
+    for &id in &order {
+        depends_on_humn[id] = id == humn
+            || match monkeys[id] {
+                Monkey::Number(_) => false,
+                Monkey::Calculated(_, left, right) => {
+                    depends_on_humn[left] || depends_on_humn[right]
                 }
-            }
-        }
+            };
     }
+
+    Ok(Monkeys {
+        monkeys,
+        order,
+        root,
+        humn,
+        depends_on_humn,
+    })
 }
 
-fn parse(input: &str) -> HashMap<&str, Monkey> {
-    let mut map = HashMap::new();
-    for line in input.lines() {
-        let (monkey, name) = parse_one(line);
-        map.insert(name, monkey);
-    }
+/// `root: pppw + sjmn` or `dbpl: 5`
+fn monkey_line(input: &str) -> IResult<&str, (&str, Job<'_>)> {
+    let number = map(parse::signed, Job::Number);
+    let operator = map(one_of("+-*/"), Operator::from);
+    let calculated = map(
+        tuple((alpha1, tag(" "), operator, tag(" "), alpha1)),
+        |(left, _, operator, _, right)| Job::Calculated(operator, left, right),
+    );
 
-    map
+    separated_pair(alpha1, tag(": "), alt((number, calculated)))(input)
 }
 
-fn parse_one(input: &str) -> (Monkey, &str) {
-    let parts = input.split(": ").collect::<Vec<_>>();
-    let name = parts[0];
-    match parts[1].parse::<i64>() {
-        Ok(number) => (Monkey::Number(number), name),
-        Err(_) => {
-            let parts: Vec<_> = parts[1].split(" ").collect();
-            let left = parts[0];
-            let operator = parts[1];
-            let right = parts[2];
-            (Monkey::Calculated(operator.into(), left, right), name)
+impl Operator {
+    /// `None` when dividing by 0 or on overflow
+    fn apply(self, left: Rational, right: Rational) -> Option<Rational> {
+        match self {
+            Operator::Add => left.checked_add(right),
+            Operator::Multiply => left.checked_mul(right),
+            Operator::Divide => left.checked_div(right),
+            Operator::Subtract => left.checked_sub(right),
         }
     }
 }
 
-impl From<&str> for Operator {
-    fn from(input: &str) -> Self {
+impl From<char> for Operator {
+    fn from(input: char) -> Self {
         match input {
-            "+" => Operator::Add,
-            "*" => Operator::Multiply,
-            "/" => Operator::Divide,
-            "-" => Operator::Subtract,
+            '+' => Operator::Add,
+            '*' => Operator::Multiply,
+            '/' => Operator::Divide,
+            '-' => Operator::Subtract,
             _ => panic!("Unknown operator: {}", input),
         }
     }
 }
 
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Subtract => "-",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Humn => write!(f, "humn"),
+            Expr::Calculated(operator, left, right) => {
+                write!(f, "({} {} {})", left, operator, right)
+            }
+        }
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.left, self.right)
+    }
+}
+
+impl Display for MonkeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonkeyError::Parse(e) => write!(f, "{}", e),
+            MonkeyError::Duplicate(name) => write!(f, "{} is defined more than once", name),
+            MonkeyError::Unknown { monkey, name } => {
+                write!(f, "{} listens to {}, who isn't defined", monkey, name)
+            }
+            MonkeyError::Cycle(names) => {
+                write!(f, "monkeys listen to each other: {}", names.join(", "))
+            }
+            MonkeyError::Missing(name) => write!(f, "there's no {}", name),
+            MonkeyError::RootNumber => write!(f, "root yells a number instead of comparing two"),
+            MonkeyError::HumnCalculated => {
+                write!(f, "humn works out a number instead of yelling one")
+            }
+        }
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NonLinear => write!(f, "humn isn't linear"),
+            SolveError::DivisionByZero => write!(f, "a monkey divides by 0"),
+            SolveError::AnyValue => write!(f, "any value of humn works"),
+            SolveError::NoValue => write!(f, "no value of humn works"),
+            SolveError::NotInteger(humn) => write!(f, "humn would have to be {}", humn),
+            SolveError::Overflow => write!(f, "the numbers got too big"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,4 +444,144 @@ mod tests {
         let input = advent_of_code::read_file("examples", 21);
         assert_eq!(part_two(&input), Some(301));
     }
+
+    #[test]
+    fn test_part_one_errors() {
+        let input = "root: aaaa + bbbb\naaaa: bbbb / zero\nbbbb: 4\nzero: 0\nhumn: 1";
+        assert_eq!(part_one(input), None);
+
+        let input = "root: aaaa * aaaa\naaaa: bbbb * bbbb\nbbbb: 1000000000\nhumn: 1";
+        assert_eq!(part_one(input), None);
+    }
+
+    #[test]
+    fn test_equation() {
+        let input = advent_of_code::read_file("examples", 21);
+        let equation = parse(&input).unwrap().equation();
+        assert_eq!(equation.to_string(), "((4 + (2 * (humn - 3))) / 4) = 150");
+    }
+
+    fn solve(input: &str) -> Result<i64, SolveError> {
+        parse(input).unwrap().equation().solve()
+    }
+
+    #[test]
+    fn test_depends_on_humn() {
+        let input = advent_of_code::read_file("examples", 21);
+        let monkeys = parse(&input).unwrap();
+
+        // ids follow the lines
+        let mut depends: Vec<_> = input
+            .lines()
+            .zip(&monkeys.depends_on_humn)
+            .filter(|(_, depends)| **depends)
+            .map(|(line, _)| &line[..4])
+            .collect();
+        depends.sort();
+        assert_eq!(
+            depends,
+            vec!["cczh", "humn", "lgvd", "pppw", "ptdq", "root"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_monkeys() {
+        let error = |input| parse(input).err().unwrap().to_string();
+
+        assert_eq!(
+            error("root: aaaa + bbbb\naaaa: 1\nbbbb: 2\naaaa: 3\nhumn: 4"),
+            "aaaa is defined more than once"
+        );
+        assert_eq!(
+            error("root: aaaa + bbbb\naaaa: 1\nhumn: 4"),
+            "root listens to bbbb, who isn't defined"
+        );
+        assert_eq!(
+            error("root: aaaa + bbbb\naaaa: bbbb * 2\nbbbb: 2\nhumn: 4"),
+            "line 2, column 14: expected Alphabetic"
+        );
+        assert_eq!(error("aaaa: 1\nhumn: 4"), "there's no root");
+        assert_eq!(
+            error("root: aaaa + bbbb\naaaa: 1\nbbbb: 2"),
+            "there's no humn"
+        );
+
+        assert_eq!(
+            error("root: 5\nhumn: 4"),
+            "root yells a number instead of comparing two"
+        );
+        assert_eq!(
+            error("root: aaaa + humn\naaaa: 1\nhumn: aaaa * aaaa"),
+            "humn works out a number instead of yelling one"
+        );
+
+        let cycle =
+            error("root: aaaa + humn\naaaa: bbbb - cccc\nbbbb: aaaa / cccc\ncccc: 1\nhumn: 4");
+        assert_eq!(cycle, "monkeys listen to each other: aaaa, bbbb");
+    }
+
+    #[test]
+    fn test_humn_twice() {
+        // 3 * humn = humn + 10
+        let input =
+            "root: aaaa + bbbb\naaaa: humn * three\nthree: 3\nbbbb: humn + ten\nten: 10\nhumn: 1";
+        assert_eq!(solve(input), Ok(5));
+
+        // humn / 2 - humn / 4 = 8, and the quarters never show up as integers on the way
+        let input = "root: aaaa - bbbb
+aaaa: half - quarter
+half: humn / two
+quarter: humn / four
+two: 2
+four: 4
+bbbb: 8
+humn: 1";
+        assert_eq!(solve(input), Ok(32));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let input = "root: aaaa + bbbb
+aaaa: humn * humn
+bbbb: 4
+humn: 1";
+        assert_eq!(solve(input), Err(SolveError::NonLinear));
+
+        let input = "root: aaaa + bbbb
+aaaa: bbbb / humn
+bbbb: 4
+humn: 1";
+        assert_eq!(solve(input), Err(SolveError::NonLinear));
+
+        // 2 * humn = 5
+        let input = "root: aaaa + bbbb
+aaaa: humn * two
+two: 2
+bbbb: 5
+humn: 1";
+        let rational = Rational::new(5, 2).unwrap();
+        assert_eq!(solve(input), Err(SolveError::NotInteger(rational)));
+
+        let input = "root: aaaa + bbbb
+aaaa: humn - humn
+bbbb: 0
+humn: 1";
+        assert_eq!(solve(input), Err(SolveError::AnyValue));
+
+        let input = "root: aaaa + bbbb
+aaaa: humn - humn
+bbbb: 1
+humn: 1";
+        assert_eq!(solve(input), Err(SolveError::NoValue));
+
+        // (10^18)^4 doesn't fit in an i128
+        let input = "root: aaaa + bbbb
+aaaa: humn * cccc
+cccc: dddd * dddd
+dddd: eeee * eeee
+eeee: 1000000000000000000
+bbbb: 1
+humn: 1";
+        assert_eq!(solve(input), Err(SolveError::Overflow));
+    }
 }
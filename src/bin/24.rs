@@ -1,83 +1,126 @@
-use advent_of_code::helpers::{Grid, Point};
-use std::{
-    collections::{BinaryHeap, HashSet},
-    fmt::Display,
+use advent_of_code::helpers::{
+    image::{self, Image, Rgb},
+    search,
+    viz::{Color, Frame, Player},
+    Direction, Grid, Neighborhood, Point,
 };
+use std::fmt::Display;
 
 pub fn part_one(input: &str) -> Option<usize> {
     let mut board = parse(input);
+    let (top, bottom) = board.entrances();
 
-    let goal = Point {
-        x: board.at(0).width() as isize - 1,
-        y: board.at(0).height() as isize - 1,
-    };
-    let start = Location {
-        p: Point::from_x_y((0, 0)),
-        time: 1,
-    };
-
-    Some(pathfind_to(&mut board, start, goal) + 1)
+    // plus one to step out of the valley
+    Some(pathfind_to(&mut board, Location { p: top, time: 0 }, bottom) + 1)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let mut board = parse(input);
+    let (top, bottom) = board.entrances();
 
-    let end = Point {
-        x: board.at(0).width() as isize - 1,
-        y: board.at(0).height() as isize - 1,
-    };
-    let start = Point::from_x_y((0, 0));
-
-    // plus 2 because move to end spot, then move back one
-    let time = pathfind_to(&mut board, Location { p: start, time: 1 }, end) + 2;
-    // plus 2 because move to end spot, then move back one
-    let time = pathfind_to(&mut board, Location { p: end, time }, start) + 2;
-    // just plus one because we only have to move to the end spot
-    let time = pathfind_to(&mut board, Location { p: start, time }, end) + 1;
+    // each trip ends a minute after reaching the last cell, stepping out onto the entrance that
+    // the next one starts from
+    let time = pathfind_to(&mut board, Location { p: top, time: 0 }, bottom) + 1;
+    let time = pathfind_to(&mut board, Location { p: bottom, time }, top) + 1;
+    let time = pathfind_to(&mut board, Location { p: top, time }, bottom) + 1;
 
     Some(time)
 }
 
-fn pathfind_to(board: &mut Board, start: Location, goal: Point) -> usize {
-    let mut heap = BinaryHeap::new();
-    let mut seen = HashSet::new();
+/// the time the expedition reaches the cell next to the `goal` entrance
+fn pathfind_to(board: &mut Board, entrance: Location, goal: Point) -> usize {
+    let path = pathfind(board, entrance, goal);
+    path.last().expect("path ends at the goal").time
+}
 
-    // we can stay in the starting spot for a long time. this simulates that bc i forgot that was
-    // an option lmao
-    for t in 0..25 {
-        let time = t + start.time;
-        let _ = board.at(time);
+/// every position of the expedition on its quickest way from the `entrance` it starts on to the
+/// cell next to the `goal` entrance. the entrances are outside the grid, and no blizzard ever
+/// reaches them, so waiting there is always safe
+fn pathfind(board: &mut Board, entrance: Location, goal: Point) -> Vec<Location> {
+    let first = board.inside(entrance.p);
+    let last = board.inside(goal);
 
-        heap.push(Location { p: start.p, time })
-    }
+    let successors = |&Location { p, time }: &Location| {
+        let g = board.at(time + 1);
 
-    while let Some(Location { p, time }) = heap.pop() {
-        if p == goal {
-            return time;
+        let mut next: Vec<_> = if p == entrance.p {
+            vec![p, first]
+        } else {
+            g.neighbors(p, Neighborhood::ORTHOGONAL.with_self())
+                .collect()
+        };
+        if p == first {
+            next.push(entrance.p);
         }
+        next.retain(|&n| n == entrance.p || g[n] == Tile(vec![]));
 
-        let g = board.at(time + 1);
+        next.into_iter()
+            .map(|p| Location { p, time: time + 1 })
+            .collect::<Vec<_>>()
+    };
 
-        let mut neighbors = g.neighbors_of(p);
-        neighbors.push(p);
-        neighbors.retain(|p| g[p.clone()] == Tile(vec![]) && seen.insert((p.clone(), time + 1)));
+    let (_, path) =
+        search::bfs([entrance], successors, |l| l.p == last).expect("no solution found");
 
-        for p in neighbors {
-            heap.push(Location { p, time: time + 1 })
-        }
-    }
+    path
+}
+
+/// the board and the expedition's path for part one
+fn trip(input: &str) -> (Board, Vec<Location>) {
+    let mut board = parse(input);
+    let (top, bottom) = board.entrances();
+    let path = pathfind(&mut board, Location { p: top, time: 0 }, bottom);
 
-    panic!("no solution found")
+    (board, path)
+}
+
+/// the valley at each minute of the trip in part one, with the expedition highlighted
+fn frames(input: &str) -> impl Iterator<Item = Frame> {
+    let (mut board, path) = trip(input);
+
+    path.into_iter().map(move |Location { p, time }| {
+        Frame::new(board.at(time).to_string())
+            .highlight([p], Color::Green)
+            .with_caption(format!("minute {}", time))
+    })
+}
+
+/// [`frames`] as pictures, with darker blue where more blizzards overlap
+fn images(input: &str) -> Vec<Image> {
+    let (mut board, path) = trip(input);
+
+    path.into_iter()
+        .map(|Location { p, time }| {
+            let mut image = board.at(time).to_image(|tile| match tile.0.len() {
+                0 => Rgb::WHITE,
+                n => {
+                    let shade = 200u8.saturating_sub(50 * n as u8);
+                    Rgb(shade, shade, 255)
+                }
+            });
+            // the expedition isn't drawn while it waits at the entrance
+            if board.at(time).contains(p) {
+                image.set(p.x as usize, p.y as usize, Rgb::RED);
+            }
+            image.scaled(8)
+        })
+        .collect()
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 24);
+    if let Some(player) = Player::from_args() {
+        player.play(frames(input));
+    }
+    if let Some(path) = image::gif_path_from_args() {
+        image::save_gif(&images(input), 20, path).expect("could not save gif");
+    }
 
     advent_of_code::solve!(1, 24, part_one, input);
     advent_of_code::solve!(2, 24, part_two, input);
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Location {
     p: Point,
     time: usize,
@@ -91,14 +134,6 @@ struct Board {
 #[derive(Debug, Clone, PartialEq)]
 struct Tile(Vec<Direction>);
 
-#[derive(Debug, Clone, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 fn parse(input: &str) -> Board {
     let grid = input
         .lines()
@@ -118,17 +153,34 @@ fn parse(input: &str) -> Board {
 }
 
 impl Board {
+    /// the gaps in the wall, just above the top left cell and just below the bottom right one
+    fn entrances(&mut self) -> (Point, Point) {
+        let g = self.at(0);
+        let bottom = Point {
+            x: g.width() as isize - 1,
+            y: g.height() as isize,
+        };
+        (Point { x: 0, y: -1 }, bottom)
+    }
+
+    /// the cell in the grid an entrance leads into
+    fn inside(&mut self, entrance: Point) -> Point {
+        let height = self.at(0).height() as isize;
+        Point {
+            y: entrance.y.clamp(0, height - 1),
+            ..entrance
+        }
+    }
+
     fn at(&mut self, time: usize) -> &'_ Grid<Tile> {
-        if time < self.grid.len() {
-            &self.grid[time]
-        } else {
-            let old = self.grid.get(time - 1).expect("time before is available");
+        while self.grid.len() <= time {
+            let old = self.grid.last().expect("time 0 is always available");
             let new = iter_grid(old);
 
             self.grid.push(new);
-
-            &self.grid[time]
         }
+
+        &self.grid[time]
     }
 }
 
@@ -140,81 +192,33 @@ fn iter_grid(old: &Grid<Tile>) -> Grid<Tile> {
             c.0.clear()
         }
     }
-    let cols = old.len() as isize;
-    let rows = old[0].len() as isize;
-
-    for (y, row) in old.iter().enumerate() {
-        for (x, tile) in row.iter().enumerate() {
-            let x = x as isize;
-            let y = y as isize;
-
-            for dir in &tile.0 {
-                let mut point = Point::from_x_y(match dir {
-                    Direction::Up => (x, y - 1),
-                    Direction::Down => (x, y + 1),
-                    Direction::Left => (x - 1, y),
-                    Direction::Right => (x + 1, y),
-                });
-
-                point.x = point.x.rem_euclid(rows);
-                point.y = point.y.rem_euclid(cols);
-
-                new[point].0.push(dir.clone());
-            }
+    for (Point { x, y }, tile) in old.enumerate() {
+        for dir in &tile.0 {
+            let point = old.wrap(Point { x, y } + *dir);
+
+            new[point].0.push(*dir);
         }
     }
 
     new
 }
 
-impl Ord for Location {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.time.cmp(&other.time).reverse()
-    }
-}
-
-impl PartialOrd for Location {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 impl From<char> for Tile {
     fn from(c: char) -> Self {
         Tile(match c {
             '#' => panic!("wall in tile"),
             '.' => vec![],
-            t => vec![t.into()],
+            t => vec![Direction::try_from(t).expect("blizzard direction")],
         })
     }
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            '^' => Direction::Up,
-            'v' => Direction::Down,
-            '<' => Direction::Left,
-            '>' => Direction::Right,
-            d => panic!("invalid direction {d}"),
-        }
-    }
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let dir: char = self.clone().into();
-        write!(f, "{}", dir)
-    }
-}
-
 impl Display for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let d = &self.0;
         let tile = if d.len() == 1 {
-            let dir: char = d[0].clone().into();
-            dir.to_string()
-        } else if d.len() == 0 {
+            d[0].to_string()
+        } else if d.is_empty() {
             ".".to_string()
         } else if d.len() < 10 {
             d.len().to_string()
@@ -226,17 +230,6 @@ impl Display for Tile {
     }
 }
 
-impl Into<char> for Direction {
-    fn into(self) -> char {
-        match self {
-            Direction::Up => '^',
-            Direction::Down => 'v',
-            Direction::Left => '<',
-            Direction::Right => '>',
-        }
-    }
-}
-
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in &self.grid {
@@ -263,6 +256,26 @@ mod tests {
         assert_eq!(part_two(&input), Some(54));
     }
 
+    #[test]
+    fn test_path_avoids_blizzards() {
+        let input = advent_of_code::read_file("examples", 24);
+        let mut board = parse(&input);
+        let (top, bottom) = board.entrances();
+
+        for (from, to) in [(top, bottom), (bottom, top)] {
+            let path = pathfind(&mut board, Location { p: from, time: 0 }, to);
+            assert_eq!(path[0].p, from);
+            for Location { p, time } in path {
+                let g = board.at(time);
+                assert!(
+                    p == from || g[p] == Tile(vec![]),
+                    "hit a blizzard at {:?}",
+                    p
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_iter_grid() {
         let mut grid = parse(
@@ -0,0 +1,183 @@
+//! skipping ahead in simulations that eventually repeat themselves.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// a cycle in a sequence of states: the state after `start` steps is the first one that comes
+/// back, and it comes back every `length` steps after that
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Extrapolation {
+    /// the cycle that was found, or `None` if the target came before any repeat
+    pub cycle: Option<Cycle>,
+    /// the metric after `target` steps
+    pub value: isize,
+}
+
+/// runs `step` on `state` until `fingerprint` repeats, then works out what `metric` would be
+/// after `target` steps without simulating the rest.
+///
+/// the metric has to change by the same amount every time around the cycle (eg. the height of
+/// a tower), and two states with the same fingerprint have to behave the same from then on.
+pub fn extrapolate<S, K>(
+    mut state: S,
+    target: usize,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+    mut metric: impl FnMut(&S) -> isize,
+) -> Extrapolation
+where
+    K: Hash + Eq,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut metrics = Vec::new();
+
+    for steps in 0.. {
+        let value = metric(&state);
+        if steps == target {
+            return Extrapolation { cycle: None, value };
+        }
+        metrics.push(value);
+
+        if let Some(start) = seen.insert(fingerprint(&state), steps) {
+            let length = steps - start;
+            let per_cycle = value - metrics[start];
+            let cycles = (target - start) / length;
+            let rest = (target - start) % length;
+
+            return Extrapolation {
+                cycle: Some(Cycle { start, length }),
+                value: metrics[start + rest] + cycles as isize * per_cycle,
+            };
+        }
+
+        step(&mut state);
+    }
+
+    unreachable!()
+}
+
+/// brent's cycle detection, for when the whole state is cheap to compare and `f` is a pure
+/// function of it. only keeps two states around, unlike [`extrapolate`]
+pub fn brent<S>(initial: S, f: impl Fn(&S) -> S) -> Cycle
+where
+    S: Clone + Eq,
+{
+    // find the length by racing a hare ahead of a tortoise that teleports to it every power of 2
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = f(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare);
+        length += 1;
+    }
+
+    // then find the start by walking two states `length` apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = f(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 2, 3, 4, 5, ...
+    fn next(n: &usize) -> usize {
+        if *n == 5 {
+            2
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(
+            brent(0, next),
+            Cycle {
+                start: 2,
+                length: 4
+            }
+        );
+        assert_eq!(
+            brent(3, next),
+            Cycle {
+                start: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            brent(7, |_| 7),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // the metric is the sum of every state so far
+        let run = |target| {
+            extrapolate(
+                (0, 0),
+                target,
+                |(n, sum)| {
+                    *n = next(n);
+                    *sum += *n as isize;
+                },
+                |(n, _)| *n,
+                |(_, sum)| *sum,
+            )
+        };
+        let naive = |target| {
+            let mut n = 0;
+            let mut sum = 0;
+            for _ in 0..target {
+                n = next(&n);
+                sum += n as isize;
+            }
+            sum
+        };
+
+        assert_eq!(
+            run(3),
+            Extrapolation {
+                cycle: None,
+                value: 6
+            }
+        );
+        for target in [7, 10, 1000, 1001, 1002, 1003] {
+            let extrapolation = run(target);
+            assert_eq!(extrapolation.value, naive(target));
+            assert_eq!(
+                extrapolation.cycle,
+                Some(Cycle {
+                    start: 2,
+                    length: 4
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::Point;
+
+/// a compass direction on a grid where `y` grows downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// up, right, down, left
+    pub const ORTHOGONAL: [Direction; 4] = [Up, Right, Down, Left];
+    pub const DIAGONAL: [Direction; 4] = [UpRight, DownRight, DownLeft, UpLeft];
+    /// all 8 directions, clockwise from up
+    pub const ALL: [Direction; 8] = [Up, UpRight, Right, DownRight, Down, DownLeft, Left, UpLeft];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Up => Point::new(0, -1),
+            UpRight => Point::new(1, -1),
+            Right => Point::new(1, 0),
+            DownRight => Point::new(1, 1),
+            Down => Point::new(0, 1),
+            DownLeft => Point::new(-1, 1),
+            Left => Point::new(-1, 0),
+            UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// rotates by 45 degrees clockwise `times` times
+    fn rotate(self, times: usize) -> Self {
+        let index = Self::ALL.iter().position(|&d| d == self).unwrap();
+        Self::ALL[(index + times) % 8]
+    }
+
+    /// a quarter turn counter clockwise
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// a quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        Self::DIAGONAL.contains(&self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError(pub String);
+
+impl Display for ParseDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+
+/// accepts arrows (`^v<>`), `UDLR` and compass points (`NSEW`)
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Up),
+            'v' | 'D' | 'S' => Ok(Down),
+            '<' | 'L' | 'W' => Ok(Left),
+            '>' | 'R' | 'E' => Ok(Right),
+            c => Err(ParseDirectionError(c.to_string())),
+        }
+    }
+}
+
+/// accepts anything [`Direction::try_from`] does, plus two letter diagonals like `NE` or `UR`
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, None) => Direction::try_from(c),
+            (Some(a), Some(b), None) => {
+                let err = || ParseDirectionError(s.to_string());
+                let vertical = Direction::try_from(a).map_err(|_| err())?;
+                let horizontal = Direction::try_from(b).map_err(|_| err())?;
+                match (vertical, horizontal) {
+                    (Up, Right) => Ok(UpRight),
+                    (Down, Right) => Ok(DownRight),
+                    (Down, Left) => Ok(DownLeft),
+                    (Up, Left) => Ok(UpLeft),
+                    _ => Err(err()),
+                }
+            }
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Up => '^',
+            UpRight => '↗',
+            Right => '>',
+            DownRight => '↘',
+            Down => 'v',
+            DownLeft => '↙',
+            Left => '<',
+            UpLeft => '↖',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Up.turn_left(), Left);
+        assert_eq!(Left.turn_right(), Up);
+        assert_eq!(UpRight.turn_right(), DownRight);
+        assert_eq!(Down.opposite(), Up);
+        assert_eq!(DownLeft.opposite(), UpRight);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.offset() + d.opposite().offset(), Point::new(0, 0));
+            assert_eq!(d.is_diagonal(), d.offset().x != 0 && d.offset().y != 0);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Direction::try_from('^'), Ok(Up));
+        assert_eq!(Direction::try_from('R'), Ok(Right));
+        assert_eq!(Direction::try_from('S'), Ok(Down));
+        assert!(Direction::try_from('x').is_err());
+        assert_eq!("W".parse(), Ok(Left));
+        assert_eq!("NE".parse(), Ok(UpRight));
+        assert_eq!("DL".parse(), Ok(DownLeft));
+        assert!("NS".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+        assert_eq!(Up.to_string(), "^");
+    }
+}
@@ -0,0 +1,202 @@
+//! directed graphs over dense `usize` ids, eg. ones handed out by an [`Interner`](super::Interner).
+
+use std::{collections::HashSet, fmt::Display};
+
+use super::search;
+
+/// a directed graph stored as adjacency lists. nodes are `0..len()`, and adding an edge grows
+/// the graph to fit it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    edges: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// a graph with `len` nodes and no edges
+    pub fn new(len: usize) -> Self {
+        Self {
+            edges: vec![Vec::new(); len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        let len = from.max(to) + 1;
+        if self.edges.len() < len {
+            self.edges.resize(len, Vec::new());
+        }
+        self.edges[from].push(to);
+    }
+
+    /// an edge each way, for graphs like tunnels that can be walked in both directions
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize) {
+        self.add_edge(a, b);
+        self.add_edge(b, a);
+    }
+
+    /// the nodes `node` has an edge to
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.edges[node]
+    }
+
+    /// every node that can be reached from `start`, including itself
+    pub fn reachable(&self, start: usize) -> HashSet<usize> {
+        search::reachable([start], |&n| self.edges[n].iter().copied())
+    }
+
+    /// the length of the shortest path between every pair of nodes, counting each edge as 1, or
+    /// `None` if there isn't one. `distances[from][to]`, using floyd warshall
+    pub fn shortest_paths(&self) -> Vec<Vec<Option<usize>>> {
+        let len = self.len();
+        let mut distances = vec![vec![None; len]; len];
+
+        for (from, row) in distances.iter_mut().enumerate() {
+            row[from] = Some(0);
+            for &to in self.edges[from].iter() {
+                if to != from {
+                    row[to] = Some(1);
+                }
+            }
+        }
+
+        for k in 0..len {
+            let from_k = distances[k].clone();
+            for row in distances.iter_mut() {
+                let Some(to_k) = row[k] else {
+                    continue;
+                };
+                for (distance, from_k) in row.iter_mut().zip(from_k.iter()) {
+                    if let Some(from_k) = from_k {
+                        let through_k = to_k + from_k;
+                        if distance.is_none_or(|d| through_k < d) {
+                            *distance = Some(through_k);
+                        }
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// every node, ordered so that each edge goes from an earlier node to a later one. fails with
+    /// one of the cycles if there is no such order
+    pub fn topological_sort(&self) -> Result<Vec<usize>, CycleError> {
+        const UNSEEN: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNSEEN; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+
+        for root in 0..self.len() {
+            if state[root] != UNSEEN {
+                continue;
+            }
+
+            // (node, how many of its edges have been followed)
+            let mut stack = vec![(root, 0)];
+            state[root] = ON_STACK;
+
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                let Some(&to) = self.edges[node].get(*next) else {
+                    stack.pop();
+                    state[node] = DONE;
+                    order.push(node);
+                    continue;
+                };
+                *next += 1;
+
+                match state[to] {
+                    UNSEEN => {
+                        state[to] = ON_STACK;
+                        stack.push((to, 0));
+                    }
+                    ON_STACK => {
+                        let start = stack.iter().position(|&(n, _)| n == to).unwrap();
+                        return Err(CycleError(stack[start..].iter().map(|&(n, _)| n).collect()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+}
+
+impl FromIterator<(usize, usize)> for Graph {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        let mut graph = Self::default();
+        for (from, to) in iter {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+/// the nodes of a cycle, in order. the last one has an edge back to the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError(pub Vec<usize>);
+
+impl Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle through nodes {:?}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 3, 0 -> 2 -> 3, and 4 on its own
+    fn graph() -> Graph {
+        let mut graph: Graph = [(0, 1), (0, 2), (1, 3), (2, 3)].into_iter().collect();
+        graph.add_edge(4, 4);
+        graph
+    }
+
+    #[test]
+    fn test_reachable() {
+        let graph = graph();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.neighbors(0), &[1, 2]);
+        assert_eq!(graph.reachable(0), HashSet::from([0, 1, 2, 3]));
+        assert_eq!(graph.reachable(2), HashSet::from([2, 3]));
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let distances = graph().shortest_paths();
+        assert_eq!(distances[0], vec![Some(0), Some(1), Some(1), Some(2), None]);
+        assert_eq!(distances[3][0], None);
+        assert_eq!(distances[4][4], Some(0));
+
+        let mut undirected = Graph::new(3);
+        undirected.add_undirected_edge(0, 1);
+        undirected.add_undirected_edge(1, 2);
+        assert_eq!(undirected.shortest_paths()[2][0], Some(2));
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut graph: Graph = [(0, 1), (0, 2), (1, 3), (2, 3)].into_iter().collect();
+        let order = graph.topological_sort().unwrap();
+        let position = |n| order.iter().position(|&o| o == n).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position(0) < position(1) && position(1) < position(3));
+        assert!(position(2) < position(3));
+
+        graph.add_edge(3, 1);
+        assert_eq!(graph.topological_sort(), Err(CycleError(vec![1, 3])));
+        assert!(self::graph().topological_sort().is_err());
+    }
+}
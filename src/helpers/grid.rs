@@ -0,0 +1,573 @@
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+use super::Point;
+
+/// a dense, zero based grid stored as rows. `Point { x, y }` indexes column `x` of row `y`.
+#[derive(Debug, Clone)]
+pub struct Grid<T>(pub Vec<Vec<T>>);
+
+impl<T> Grid<T> {
+    /// parses one row per line of `input`, turning every char into a cell with `f`
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        input
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().map(&mut f).collect())
+            .collect()
+    }
+
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self(vec![vec![fill; width]; height])
+    }
+
+    pub fn width(&self) -> usize {
+        self.0.first().map_or(0, Vec::len)
+    }
+    pub fn height(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && p.x < self.width() as isize && p.y < self.height() as isize
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self[p])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self[p])
+        } else {
+            None
+        }
+    }
+
+    /// every point in the grid, row by row
+    pub fn iter_points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width() as isize;
+        let height = self.height() as isize;
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point { x, y }))
+    }
+
+    /// every cell along with its point, row by row
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.0.iter().enumerate().flat_map(|(y, row)| {
+            row.iter().enumerate().map(move |(x, t)| {
+                let p = Point {
+                    x: x as isize,
+                    y: y as isize,
+                };
+                (p, t)
+            })
+        })
+    }
+
+    /// the first point (row by row) whose cell matches `predicate`
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.enumerate().find(|(_, t)| predicate(t)).map(|(p, _)| p)
+    }
+
+    /// every point whose cell matches `predicate`
+    pub fn positions<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.enumerate()
+            .filter(move |(_, t)| predicate(t))
+            .map(|(p, _)| p)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.0[y]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.0.iter().map(Vec::as_slice)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.0.iter().map(move |row| &row[x])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width()).map(|x| self.column(x))
+    }
+
+    /// a new grid of the same size with `f` applied to every cell
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        self.0
+            .iter()
+            .map(|row| row.iter().map(&mut f).collect())
+            .collect()
+    }
+
+    /// moves a point that's outside of the grid back inside, as if the grid was a torus
+    pub fn wrap(&self, mut p: Point) -> Point {
+        p.ensure_between((0, self.width() as isize), (0, self.height() as isize));
+        p
+    }
+
+    /// the four orthogonal neighbors of `p` that are inside the grid
+    pub fn neighbors_of(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors(p, Neighborhood::ORTHOGONAL)
+    }
+
+    pub fn neighbors<'a>(
+        &'a self,
+        p: Point,
+        hood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = Point> + 'a {
+        hood.around(p).filter_map(move |n| {
+            if hood.wrap {
+                Some(self.wrap(n))
+            } else if self.contains(n) {
+                Some(n)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// walks from `from` in steps of `step` until the edge of the grid, not including `from`
+    /// itself. eg. `step` of `(0, -1)` looks straight up.
+    pub fn ray(&self, from: Point, step: Point) -> impl Iterator<Item = (Point, &T)> {
+        let mut p = from;
+        std::iter::from_fn(move || {
+            if step == (Point { x: 0, y: 0 }) {
+                return None;
+            }
+            p = Point {
+                x: p.x + step.x,
+                y: p.y + step.y,
+            };
+            self.get(p).map(|t| (p, t))
+        })
+    }
+
+    /// the bounds of the whole grid
+    pub fn rect(&self) -> Rect {
+        Rect::from_size(Point { x: 0, y: 0 }, self.width(), self.height())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// rows become columns. the cell at `(x, y)` moves to `(y, x)`
+    pub fn transpose(&self) -> Self {
+        (0..self.width())
+            .map(|x| self.column(x).cloned().collect())
+            .collect()
+    }
+
+    /// rotates the grid a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self {
+        let mut grid = self.transpose();
+        grid.flip_horizontal_mut();
+        grid
+    }
+
+    /// rotates the grid a quarter turn counter clockwise
+    pub fn rotate_ccw(&self) -> Self {
+        let mut grid = self.transpose();
+        grid.flip_vertical_mut();
+        grid
+    }
+
+    /// mirrors the grid left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let mut grid = self.clone();
+        grid.flip_horizontal_mut();
+        grid
+    }
+
+    /// mirrors the grid top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let mut grid = self.clone();
+        grid.flip_vertical_mut();
+        grid
+    }
+
+    /// copies out the part of the grid inside `rect`, clipped to the grid's bounds
+    pub fn sub_grid(&self, rect: Rect) -> Self {
+        let Some(rect) = rect.intersection(&self.rect()) else {
+            return Grid(Vec::new());
+        };
+
+        (rect.min.y..=rect.max.y)
+            .map(|y| self.0[y as usize][rect.min.x as usize..=rect.max.x as usize].to_vec())
+            .collect()
+    }
+
+    /// writes `other` into this grid with its top left corner at `at`. anything that would land
+    /// outside of this grid is dropped
+    pub fn paste(&mut self, at: Point, other: &Grid<T>) {
+        for (p, t) in other.enumerate() {
+            let p = Point {
+                x: p.x + at.x,
+                y: p.y + at.y,
+            };
+            if let Some(cell) = self.get_mut(p) {
+                *cell = t.clone();
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    fn flip_horizontal_mut(&mut self) {
+        for row in self.0.iter_mut() {
+            row.reverse();
+        }
+    }
+
+    fn flip_vertical_mut(&mut self) {
+        self.0.reverse();
+    }
+}
+
+/// an axis aligned rectangle, `min` and `max` both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(min: Point, width: usize, height: usize) -> Self {
+        Self {
+            min,
+            max: Point {
+                x: min.x + width as isize - 1,
+                y: min.y + height as isize - 1,
+            },
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect {
+            min: Point {
+                x: self.min.x.max(other.min.x),
+                y: self.min.y.max(other.min.y),
+            },
+            max: Point {
+                x: self.max.x.min(other.max.x),
+                y: self.max.y.min(other.max.y),
+            },
+        };
+        (rect.width() > 0 && rect.height() > 0).then_some(rect)
+    }
+
+    /// every point in the rectangle, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Rect { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point { x, y }))
+    }
+}
+
+/// which points count as neighbors in [`Grid::neighbors`]. neighbors that fall outside the grid
+/// are skipped, unless the neighborhood is [`wrapping`](Neighborhood::wrapping).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighborhood<'a> {
+    offsets: &'a [Point],
+    wrap: bool,
+    include_self: bool,
+}
+
+const ORTHOGONAL: [Point; 4] = [
+    Point { x: 0, y: -1 },
+    Point { x: 1, y: 0 },
+    Point { x: 0, y: 1 },
+    Point { x: -1, y: 0 },
+];
+
+const DIAGONAL: [Point; 4] = [
+    Point { x: 1, y: -1 },
+    Point { x: 1, y: 1 },
+    Point { x: -1, y: 1 },
+    Point { x: -1, y: -1 },
+];
+
+const ALL: [Point; 8] = [
+    Point { x: 0, y: -1 },
+    Point { x: 1, y: -1 },
+    Point { x: 1, y: 0 },
+    Point { x: 1, y: 1 },
+    Point { x: 0, y: 1 },
+    Point { x: -1, y: 1 },
+    Point { x: -1, y: 0 },
+    Point { x: -1, y: -1 },
+];
+
+impl Neighborhood<'static> {
+    /// up, right, down, left
+    pub const ORTHOGONAL: Self = Self::custom(&ORTHOGONAL);
+    pub const DIAGONAL: Self = Self::custom(&DIAGONAL);
+    /// all 8 surrounding points (king moves), clockwise from up
+    pub const ALL: Self = Self::custom(&ALL);
+}
+
+impl<'a> Neighborhood<'a> {
+    /// neighbors at arbitrary offsets from the point
+    pub const fn custom(offsets: &'a [Point]) -> Self {
+        Self {
+            offsets,
+            wrap: false,
+            include_self: false,
+        }
+    }
+
+    /// neighbors that fall off one edge come back in on the opposite one
+    pub const fn wrapping(self) -> Self {
+        Self { wrap: true, ..self }
+    }
+
+    /// the point itself is yielded first, before its neighbors
+    pub const fn with_self(self) -> Self {
+        Self {
+            include_self: true,
+            ..self
+        }
+    }
+
+    /// every neighbor of `p`, without any bounds or wrapping applied
+    pub fn around(self, p: Point) -> impl Iterator<Item = Point> + 'a {
+        let own = self.include_self.then_some(Point { x: 0, y: 0 });
+
+        own.into_iter()
+            .chain(self.offsets.iter().copied())
+            .map(move |offset| Point {
+                x: p.x + offset.x,
+                y: p.y + offset.y,
+            })
+    }
+}
+
+impl<T> PartialEq for Grid<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> FromIterator<Vec<T>> for Grid<T> {
+    fn from_iter<I: IntoIterator<Item = Vec<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.0 {
+            for col in row {
+                write!(f, "{}", col)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Deref for Grid<T> {
+    fn deref(&self) -> &Vec<Vec<T>> {
+        &self.0
+    }
+    type Target = Vec<Vec<T>>;
+}
+
+impl<T> DerefMut for Grid<T> {
+    fn deref_mut(&mut self) -> &mut Vec<Vec<T>> {
+        &mut self.0
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        &self.0[index.y as usize][index.x as usize]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
+        &mut self.0[index.y as usize][index.x as usize]
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = Vec<T>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap())
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point { x: 2, y: 1 }], 6);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(Grid::new(2, 1, '.').to_string(), "..\n");
+    }
+
+    #[test]
+    fn test_get() {
+        let mut grid = grid();
+        assert_eq!(grid.get(Point { x: 0, y: 1 }), Some(&4));
+        assert_eq!(grid.get(Point { x: 3, y: 0 }), None);
+        assert_eq!(grid.get(Point { x: 0, y: -1 }), None);
+
+        *grid.get_mut(Point { x: 1, y: 0 }).unwrap() = 9;
+        assert_eq!(grid.row(0), &[1, 9, 3]);
+        assert!(grid.get_mut(Point { x: 1, y: 2 }).is_none());
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = grid();
+        assert_eq!(grid.iter_points().count(), 6);
+        assert_eq!(grid.enumerate().nth(4), Some((Point { x: 1, y: 1 }, &5)));
+        assert_eq!(grid.find(|&n| n > 4), Some(Point { x: 1, y: 1 }));
+        assert_eq!(grid.find(|&n| n > 9), None);
+        assert_eq!(
+            grid.positions(|n| n % 2 == 1).collect::<Vec<_>>(),
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 2, y: 0 },
+                Point { x: 1, y: 1 }
+            ]
+        );
+        assert_eq!(grid.rows().nth(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.map(|n| n * 2).to_string(), "246\n81012\n");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = grid();
+        let corner = Point { x: 0, y: 0 };
+        let collect = |hood| grid.neighbors(corner, hood).collect::<Vec<_>>();
+
+        assert_eq!(
+            collect(Neighborhood::ORTHOGONAL),
+            vec![Point { x: 1, y: 0 }, Point { x: 0, y: 1 }]
+        );
+        assert_eq!(grid.neighbors_of(corner).count(), 2);
+        assert_eq!(collect(Neighborhood::ALL).len(), 3);
+        assert_eq!(collect(Neighborhood::DIAGONAL), vec![Point { x: 1, y: 1 }]);
+        assert_eq!(collect(Neighborhood::ORTHOGONAL.with_self())[0], corner);
+        assert_eq!(
+            collect(Neighborhood::ORTHOGONAL.wrapping()),
+            vec![
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 2, y: 0 }
+            ]
+        );
+        let knight = [Point { x: 1, y: 2 }, Point { x: 2, y: 1 }];
+        assert_eq!(
+            collect(Neighborhood::custom(&knight)),
+            vec![Point { x: 2, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = grid();
+        assert_eq!(grid.wrap(Point { x: 3, y: -1 }), Point { x: 0, y: 1 });
+        assert_eq!(grid.wrap(Point { x: 2, y: 1 }), Point { x: 2, y: 1 });
+
+        let empty: Grid<u8> = Grid(vec![]);
+        let p = Point { x: 3, y: -1 };
+        assert_eq!(empty.wrap(p), p);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate_cw().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "321\n654\n");
+        assert_eq!(grid.flip_vertical().to_string(), "456\n123\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(
+            grid.rotate_cw().rotate_cw(),
+            grid.flip_horizontal().flip_vertical()
+        );
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let mut grid = grid();
+        let rect = Rect::from_size(Point { x: 1, y: 0 }, 2, 2);
+        let sub = grid.sub_grid(rect);
+        assert_eq!(sub.to_string(), "23\n56\n");
+        // clipped to the grid
+        let rect = Rect::new(Point { x: 2, y: -1 }, Point { x: 5, y: 0 });
+        assert_eq!(grid.sub_grid(rect).to_string(), "3\n");
+
+        grid.paste(Point { x: 0, y: 0 }, &sub.rotate_cw());
+        assert_eq!(grid.to_string(), "523\n636\n");
+        grid.paste(Point { x: 2, y: 1 }, &sub);
+        assert_eq!(grid.to_string(), "523\n632\n");
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = grid();
+        let from = Point { x: 0, y: 1 };
+        let right: Vec<_> = grid
+            .ray(from, Point { x: 1, y: 0 })
+            .map(|(_, t)| *t)
+            .collect();
+        assert_eq!(right, vec![5, 6]);
+        assert_eq!(grid.ray(from, Point { x: -1, y: 0 }).count(), 0);
+        assert_eq!(
+            grid.ray(from, Point { x: 1, y: -1 }).collect::<Vec<_>>(),
+            vec![(Point { x: 1, y: 0 }, &2)]
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! exporting grids as pictures, for sharing results without screenshots of the terminal.
+//!
+//! everything is encoded by hand so there's no image dependency: PPM and SVG are plain text-ish
+//! formats, PNG is written without compression, and GIF uses LZW codes that never grow past
+//! their starting width. the files are bigger than they'd need to be, but every viewer opens
+//! them.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    process,
+};
+
+use super::{Grid, Point, SparseGrid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(64, 160, 43);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(223, 142, 29);
+}
+
+/// `#rrggbb`
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// every pixel blown up into a `factor` by `factor` square, since one pixel per cell is
+    /// tiny for most puzzles
+    pub fn scaled(&self, factor: usize) -> Self {
+        let mut scaled = Self::new(self.width * factor, self.height * factor, Rgb::BLACK);
+        for y in 0..scaled.height {
+            for x in 0..scaled.width {
+                scaled.set(x, y, self.get(x / factor, y / factor));
+            }
+        }
+        scaled
+    }
+
+    /// binary PPM (`P6`)
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for Rgb(r, g, b) in self.pixels.iter() {
+            bytes.extend([r, g, b]);
+        }
+        bytes
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, rgb, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        // every row starts with its filter type, 0 for none
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            for Rgb(r, g, b) in row {
+                raw.extend([r, g, b]);
+            }
+        }
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// one `rect` per run of same colored pixels in a row, `scale` units per pixel
+    pub fn to_svg(&self, scale: usize) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            self.width * scale,
+            self.height * scale,
+            self.width,
+            self.height
+        );
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let color = self.get(x, y);
+                let run = (x..self.width)
+                    .take_while(|&x| self.get(x, y) == color)
+                    .count();
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>\n",
+                    x, y, run, color
+                ));
+                x += run;
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// writes the image in the format matching the extension: `ppm`, `png` or `svg`
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png(),
+            Some("svg") => self.to_svg(1).into_bytes(),
+            _ => return Err(unsupported(path)),
+        };
+        fs::write(path, bytes)
+    }
+}
+
+impl<T> Grid<T> {
+    /// a pixel per cell, colored by `color`
+    pub fn to_image(&self, color: impl Fn(&T) -> Rgb) -> Image {
+        let mut image = Image::new(self.width(), self.height(), Rgb::BLACK);
+        for (Point { x, y }, t) in self.enumerate() {
+            image.set(x as usize, y as usize, color(t));
+        }
+        image
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// a pixel per cell of the bounding box, laid out like the grid is displayed. `color` gets
+    /// `None` for unset cells
+    pub fn to_image(&self, color: impl Fn(Option<&T>) -> Rgb) -> Image {
+        let Some(bounds) = self.bounds() else {
+            return Image::new(0, 0, Rgb::BLACK);
+        };
+
+        let mut image = Image::new(bounds.width(), bounds.height(), Rgb::BLACK);
+        for p in bounds.points() {
+            let x = p.x - bounds.min.x;
+            let y = match self.is_flipped_y() {
+                true => bounds.max.y - p.y,
+                false => p.y - bounds.min.y,
+            };
+            image.set(x as usize, y as usize, color(self.get(p)));
+        }
+        image
+    }
+}
+
+/// an animated GIF that loops forever, showing each frame for `delay` hundredths of a second.
+/// the frames have to be the same size and use at most 256 colors between them
+pub fn to_gif(frames: &[Image], delay: u16) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(ErrorKind::InvalidInput, message.to_string());
+    let Some(first) = frames.first() else {
+        return Err(invalid("no frames"));
+    };
+    if frames
+        .iter()
+        .any(|f| (f.width, f.height) != (first.width, first.height))
+    {
+        return Err(invalid("frames are different sizes"));
+    }
+    let (Ok(width), Ok(height)) = (u16::try_from(first.width), u16::try_from(first.height)) else {
+        return Err(invalid("frames are more than 65535 pixels wide or tall"));
+    };
+
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut indices: HashMap<Rgb, u8> = HashMap::new();
+    for pixel in frames.iter().flat_map(|f| f.pixels.iter()) {
+        if !indices.contains_key(pixel) {
+            if palette.len() == 256 {
+                return Err(invalid("more than 256 colors"));
+            }
+            indices.insert(*pixel, palette.len() as u8);
+            palette.push(*pixel);
+        }
+    }
+    // the color table has 2^bits entries, and lzw needs at least 2 bits
+    let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(2);
+    palette.resize(1 << bits, Rgb::BLACK);
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend(width.to_le_bytes());
+    gif.extend(height.to_le_bytes());
+    gif.extend([0x80 | 0x70 | (bits as u8 - 1), 0, 0]);
+    for Rgb(r, g, b) in palette.iter() {
+        gif.extend([r, g, b]);
+    }
+    // loop forever
+    gif.extend([0x21, 0xff, 0x0b]);
+    gif.extend(b"NETSCAPE2.0");
+    gif.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for frame in frames {
+        gif.extend([0x21, 0xf9, 0x04, 0x00]);
+        gif.extend(delay.to_le_bytes());
+        gif.extend([0x00, 0x00]);
+
+        gif.push(0x2c);
+        gif.extend([0, 0, 0, 0]);
+        gif.extend(width.to_le_bytes());
+        gif.extend(height.to_le_bytes());
+        gif.push(0);
+
+        let pixels: Vec<u8> = frame.pixels.iter().map(|p| indices[p]).collect();
+        gif.push(bits as u8);
+        for block in lzw(&pixels, bits).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+
+    gif.push(0x3b);
+    Ok(gif)
+}
+
+/// saves the frames as an animated GIF, see [`to_gif`]
+pub fn save_gif(frames: &[Image], delay: u16, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, to_gif(frames, delay)?)
+}
+
+/// saves each frame in `dir` as `0000.{extension}`, `0001.{extension}`, ..., returning the paths
+pub fn save_numbered(
+    frames: impl IntoIterator<Item = Image>,
+    dir: impl AsRef<Path>,
+    extension: &str,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(&dir)?;
+    frames
+        .into_iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.as_ref().join(format!("{:04}.{}", i, extension));
+            frame.save(&path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// the `--gif` path the program was run with, if any. exits if the flag is there without a
+/// path, like [`super::viz::Player::from_args`] does for its flags
+pub fn gif_path_from_args() -> Option<PathBuf> {
+    let mut args = pico_args::Arguments::from_env();
+    match args.opt_value_from_str("--gif") {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn unsupported(path: &Path) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidInput,
+        format!("unsupported image format: {}", path.display()),
+    )
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<_> = data.chunks(u16::MAX as usize).collect();
+    if blocks.is_empty() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let last = i == blocks.len() - 1;
+        let len = block.len() as u16;
+        zlib.push(last as u8);
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(*block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend(((b << 16) | a).to_be_bytes());
+    zlib
+}
+
+/// gif flavoured lzw that never builds any strings: it sends a clear code before the decoder's
+/// table would need wider codes, so every code is `bits + 1` wide
+fn lzw(pixels: &[u8], bits: u32) -> Vec<u8> {
+    let clear = 1u32 << bits;
+    let end = clear + 1;
+    let width = bits + 1;
+    // the decoder adds an entry for every code after the first following a clear, and widens
+    // its codes once the next entry would be 2^width
+    let run = (1usize << bits) - 2;
+
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut buffered = 0;
+    let mut write = |code: u32| {
+        buffer |= code << buffered;
+        buffered += width;
+        while buffered >= 8 {
+            bytes.push(buffer as u8);
+            buffer >>= 8;
+            buffered -= 8;
+        }
+    };
+
+    for chunk in pixels.chunks(run) {
+        write(clear);
+        for &pixel in chunk {
+            write(pixel as u32);
+        }
+    }
+    write(end);
+    if buffered > 0 {
+        bytes.push(buffer as u8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> Image {
+        let mut image = Image::new(3, 2, Rgb::WHITE);
+        image.set(1, 0, Rgb::RED);
+        image.set(2, 1, Rgb::BLUE);
+        image
+    }
+
+    /// a plain gif lzw decoder, to check the encoder against
+    fn unlzw(bytes: &[u8], bits: u32) -> Vec<u8> {
+        let clear = 1usize << bits;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            *table = (0..clear).map(|i| vec![i as u8]).collect();
+            table.extend([vec![], vec![]]);
+        };
+        reset(&mut table);
+
+        let mut width = bits + 1;
+        let mut position = 0;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        loop {
+            let mut code = 0;
+            for i in 0..width {
+                let bit = (bytes[(position + i as usize) / 8] >> ((position + i as usize) % 8)) & 1;
+                code |= (bit as usize) << i;
+            }
+            position += width as usize;
+
+            if code == clear {
+                reset(&mut table);
+                width = bits + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("bad code"),
+            };
+            if let Some(previous) = previous {
+                table.push([previous, vec![entry[0]]].concat());
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            out.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw() {
+        for bits in [2, 3, 8] {
+            let pixels: Vec<u8> = (0..1000).map(|i| ((i * 7) % (1 << bits)) as u8).collect();
+            assert_eq!(unlzw(&lzw(&pixels, bits), bits), pixels);
+        }
+    }
+
+    #[test]
+    fn test_formats() {
+        let image = image();
+
+        let ppm = image.to_ppm();
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 3 * 6);
+        assert_eq!(&ppm[11 + 3..11 + 6], &[220, 50, 47]);
+
+        let png = image.to_png();
+        assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+        // IEND always has the same crc
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+
+        let svg = image.to_svg(10);
+        assert!(svg.contains("width=\"30\" height=\"20\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"1\" width=\"2\" height=\"1\" fill=\"#ffffff\"/>"));
+        assert_eq!(svg.matches("<rect").count(), 5);
+
+        let gif = to_gif(&[image.clone(), image.scaled(1)], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(gif.last(), Some(&0x3b));
+        assert!(to_gif(&[image.clone(), image.scaled(2)], 10).is_err());
+        assert!(to_gif(&[], 10).is_err());
+        let wide = Image::new(u16::MAX as usize + 1, 1, Rgb::WHITE);
+        assert_eq!(
+            to_gif(&[wide], 10).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        let tall = Image::new(1, u16::MAX as usize, Rgb::WHITE);
+        assert!(to_gif(&[tall], 10).is_ok());
+    }
+
+    #[test]
+    fn test_grids() {
+        let grid = Grid::parse("#.\n.#\n", |c| c == '#');
+        let image = grid.to_image(|&wall| if wall { Rgb::BLACK } else { Rgb::WHITE });
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(image.get(1, 1), Rgb::BLACK);
+        assert_eq!(image.get(1, 0), Rgb::WHITE);
+        assert_eq!(image.scaled(3).get(5, 0), Rgb::WHITE);
+
+        let sparse: SparseGrid<char> = [(Point::new(0, 0), '#'), (Point::new(2, 1), '#')]
+            .into_iter()
+            .collect();
+        let color = |c: Option<&char>| c.map_or(Rgb::WHITE, |_| Rgb::BLACK);
+        let image = sparse.to_image(color);
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image.get(2, 1), Rgb::BLACK);
+
+        let image = sparse.with_flipped_y().to_image(color);
+        assert_eq!(image.get(2, 0), Rgb::BLACK);
+        assert_eq!(image.get(0, 1), Rgb::BLACK);
+    }
+}
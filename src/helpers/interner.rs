@@ -0,0 +1,94 @@
+use std::{collections::HashMap, ops::Index};
+
+/// hands out dense ids (`0, 1, 2, ...`) for names, in the order they are first seen, so they can
+/// index into a `Vec` instead of hashing strings everywhere
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interner {
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the id of `name`, giving it the next free one if it hasn't been seen before
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    /// the id of `name`, without interning it
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// the name behind `id`, or `None` if it was never handed out
+    pub fn name(&self, id: usize) -> Option<&str> {
+        self.names.get(id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// every `(id, name)` pair, in id order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.names.iter().map(String::as_str).enumerate()
+    }
+}
+
+impl Index<usize> for Interner {
+    type Output = str;
+
+    fn index(&self, id: usize) -> &Self::Output {
+        &self.names[id]
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Interner {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        for name in iter {
+            interner.intern(name);
+        }
+        interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("AA"), 0);
+        assert_eq!(names.intern("BB"), 1);
+        assert_eq!(names.intern("AA"), 0);
+        assert_eq!(names.len(), 2);
+
+        assert_eq!(names.get("BB"), Some(1));
+        assert_eq!(names.get("CC"), None);
+        assert_eq!(names.name(1), Some("BB"));
+        assert_eq!(names.name(2), None);
+        assert_eq!(&names[0], "AA");
+
+        let collected: Interner = ["AA", "BB", "AA"].into_iter().collect();
+        assert_eq!(collected, names);
+        assert_eq!(
+            collected.iter().collect::<Vec<_>>(),
+            vec![(0, "AA"), (1, "BB")]
+        );
+    }
+}
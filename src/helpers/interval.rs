@@ -0,0 +1,233 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// the integers from `start` to `end`, both included. never empty, so operations that could end
+/// up with nothing return an `Option`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: isize,
+    pub end: isize,
+}
+
+impl Interval {
+    /// `None` if `end` comes before `start`
+    pub fn new(start: isize, end: isize) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// how many integers are in the interval
+    #[allow(clippy::len_without_is_empty)] // an interval is never empty
+    pub fn len(&self) -> usize {
+        self.start.abs_diff(self.end) + 1
+    }
+
+    pub fn contains(&self, n: isize) -> bool {
+        self.start <= n && n <= self.end
+    }
+
+    /// whether every integer in `other` is also in this one
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// whether the two overlap or sit right next to each other, ie. their union is one interval
+    pub fn touches(&self, other: &Self) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// the two as one interval, or `None` if there would be a gap between them
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl From<RangeInclusive<isize>> for Interval {
+    /// panics if the range is empty
+    fn from(range: RangeInclusive<isize>) -> Self {
+        Self::new(*range.start(), *range.end()).expect("non empty range")
+    }
+}
+
+impl From<Interval> for RangeInclusive<isize> {
+    fn from(interval: Interval) -> Self {
+        interval.start..=interval.end
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// a set of integers stored as sorted, disjoint intervals. neighbouring intervals are merged, so
+/// each one is separated from the next by at least one missing integer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// how many integers are in the set
+    pub fn len(&self) -> usize {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// the intervals making up the set, in order
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn contains(&self, n: isize) -> bool {
+        let i = self.intervals.partition_point(|i| i.end < n);
+        self.intervals.get(i).is_some_and(|i| i.contains(n))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // everything from `first` up to `last` touches the new interval, so gets merged into it
+        let first = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|i| i.start <= interval.end.saturating_add(1));
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |a, b| a.union(b).unwrap());
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+
+        // only the first and last overlapping intervals can stick out either side
+        let mut kept = Vec::new();
+        if let Some(i) = self.intervals[first..last].first() {
+            kept.extend(Interval::new(i.start, interval.start - 1));
+        }
+        if let Some(i) = self.intervals[first..last].last() {
+            kept.extend(Interval::new(interval.end + 1, i.end));
+        }
+        self.intervals.splice(first..last, kept);
+    }
+
+    /// the parts of `within` that aren't in the set, in order
+    pub fn gaps(&self, within: Interval) -> Vec<Interval> {
+        let mut gaps = RangeSet::new();
+        gaps.insert(within);
+        for i in self.intervals.iter() {
+            gaps.remove(*i);
+        }
+        gaps.intervals
+    }
+}
+
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: isize, end: isize) -> Interval {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = interval(2, 6);
+        let b = interval(4, 8);
+        let c = interval(7, 9);
+
+        assert_eq!(a.len(), 5);
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.overlaps(&b) && !a.overlaps(&c));
+        assert!(a.touches(&c));
+        assert!(interval(1, 10).contains_interval(&a) && !a.contains_interval(&b));
+
+        assert_eq!(a.intersection(&b), Some(interval(4, 6)));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.union(&c), Some(interval(2, 9)));
+        assert_eq!(a.union(&interval(8, 9)), None);
+
+        assert_eq!(Interval::new(3, 2), None);
+        assert_eq!(Interval::from(-3..=3).len(), 7);
+        assert_eq!(a.to_string(), "2..=6");
+    }
+
+    #[test]
+    fn test_range_set() {
+        let mut set: RangeSet = [interval(10, 12), interval(0, 2), interval(4, 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 8);
+        assert!(set.contains(11) && !set.contains(3));
+
+        set.insert(interval(3, 3));
+        assert_eq!(set.intervals(), &[interval(0, 5), interval(10, 12)]);
+
+        set.insert(interval(-2, 20));
+        assert_eq!(set.intervals(), &[interval(-2, 20)]);
+
+        set.remove(interval(5, 7));
+        set.remove(interval(15, 30));
+        assert_eq!(set.intervals(), &[interval(-2, 4), interval(8, 14)]);
+        assert_eq!(set.len(), 14);
+
+        assert_eq!(set.gaps(interval(0, 10)), vec![interval(5, 7)]);
+
+        set.remove(interval(-5, 50));
+        assert!(set.is_empty());
+        assert_eq!(set.gaps(interval(1, 2)), vec![interval(1, 2)]);
+    }
+
+    #[test]
+    fn test_range_set_against_naive() {
+        // inserts and removes a bunch of overlapping intervals and checks every point
+        let mut set = RangeSet::new();
+        let mut naive = [false; 40];
+        for n in 0..30isize {
+            let start = (n * 7) % 31;
+            let i = interval(start, (start + n % 5).min(39));
+            if n % 3 == 0 {
+                set.remove(i);
+            } else {
+                set.insert(i);
+            }
+            for p in i.start..=i.end {
+                naive[p as usize] = n % 3 != 0;
+            }
+
+            assert_eq!(set.len(), naive.iter().filter(|&&b| b).count());
+            for (p, &b) in naive.iter().enumerate() {
+                assert_eq!(set.contains(p as isize), b);
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! memoization on top of the `cached` crate.
+//!
+//! for recursive functions that need some context (the parsed input, usually), pass a [`Memo`]
+//! down the calls and wrap the body in [`Memo::get_or_insert_with`]:
+//!
+//! ```
+//! use advent_of_code::helpers::memo::Memo;
+//!
+//! fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+//!     memo.get_or_insert_with(n, |memo| match n {
+//!         0 | 1 => n,
+//!         n => fib(n - 1, memo) + fib(n - 2, memo),
+//!     })
+//! }
+//!
+//! let mut memo = Memo::new();
+//! assert_eq!(fib(80, &mut memo), 23416728348467685);
+//! assert_eq!(memo.stats().misses, 81);
+//! ```
+//!
+//! for functions that only depend on their arguments, the `#[cached]` attribute does the same
+//! with a global cache named after the function in upper case. that cache lives for the whole
+//! program, so it's shared between the example and the real input: don't key it on anything that
+//! only makes sense for one input, like an index into it.
+//!
+//! ```
+//! use advent_of_code::helpers::memo;
+//! use cached::proc_macro::cached;
+//!
+//! #[cached]
+//! fn ways_to_make(n: u64) -> u64 {
+//!     match n {
+//!         0 => 1,
+//!         n => (1..=n.min(3)).map(|step| ways_to_make(n - step)).sum(),
+//!     }
+//! }
+//!
+//! assert_eq!(ways_to_make(30), 53798080);
+//! let stats = memo::stats_of(&*WAYS_TO_MAKE.lock().unwrap());
+//! assert_eq!(stats.misses, 31);
+//! ```
+//!
+//! either way, the [`MemoStats`] say how much the cache saved, and print as a single line.
+
+use std::{fmt::Display, hash::Hash};
+
+use cached::{Cached, UnboundCache};
+
+/// a cache from `K` to `V` that counts its hits and misses
+#[derive(Debug, Clone)]
+pub struct Memo<K: Hash + Eq, V> {
+    cache: UnboundCache<K, V>,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            cache: UnboundCache::new(),
+        }
+    }
+
+    /// the cached value for `key`, or the one `f` works out if there isn't one yet. `f` gets the
+    /// memo back so it can recurse
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.cache_get(&key) {
+            return value.clone();
+        }
+
+        let value = f(self);
+        self.cache.cache_set(key, value.clone());
+        value
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.cache.cache_get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.cache_size()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        stats_of(&self.cache)
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// how many values are cached
+    pub size: usize,
+}
+
+impl MemoStats {
+    /// the fraction of lookups that were already cached, 0 if there weren't any
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} cached",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.size
+        )
+    }
+}
+
+/// the stats of any `cached` cache, including the globals `#[cached]` creates
+pub fn stats_of<K, V>(cache: &impl Cached<K, V>) -> MemoStats {
+    MemoStats {
+        hits: cache.cache_hits().unwrap_or(0),
+        misses: cache.cache_misses().unwrap_or(0),
+        size: cache.cache_size(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // how many ways there are to climb `n` stairs taking 1 or 2 at a time
+    fn stairs(n: u32, memo: &mut Memo<u32, u64>) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => 1,
+            n => stairs(n - 1, memo) + stairs(n - 2, memo),
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(stairs(50, &mut memo), 20365011074);
+
+        let stats = memo.stats();
+        assert_eq!(stats.misses, 51);
+        assert_eq!(stats.hits, 48);
+        assert_eq!(stats.size, 51);
+        assert_eq!(memo.get(&10), Some(&89));
+
+        assert_eq!(stairs(50, &mut memo), 20365011074);
+        assert_eq!(memo.stats().misses, 51);
+        assert_eq!(
+            MemoStats {
+                hits: 1,
+                misses: 3,
+                size: 3
+            }
+            .to_string(),
+            "1 hits, 3 misses (25.0% hit rate), 3 cached"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! mixing a list of numbers in a circle: every number moves along it by its own value, in the
+//! order the numbers started in.
+//!
+//! [`mix`] does the moving and [`gps`] reads the result, and a [`Decryption`] puts the two
+//! together with the key, rounds and offsets of day 20's grove coordinates.
+
+use std::fmt::Display;
+
+/// how to get the grove coordinates out of the encrypted file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decryption {
+    /// every number is multiplied by this before mixing
+    pub key: isize,
+    pub rounds: usize,
+    /// how far after the 0 each coordinate is
+    pub offsets: Vec<usize>,
+}
+
+impl Decryption {
+    pub fn part_one() -> Self {
+        Self {
+            key: 1,
+            rounds: 1,
+            offsets: vec![1000, 2000, 3000],
+        }
+    }
+
+    pub fn part_two() -> Self {
+        Self {
+            key: 811589153,
+            rounds: 10,
+            ..Self::part_one()
+        }
+    }
+
+    /// the sum of the grove coordinates, or `None` if there's no 0 to count them from. `moved`
+    /// is called after every move, see [`mix`]
+    pub fn decrypt(&self, values: &[isize], moved: impl FnMut()) -> Option<isize> {
+        let values: Vec<isize> = values.iter().map(|v| v * self.key).collect();
+        gps(&mix(&values, self.rounds, moved), &self.offsets)
+    }
+}
+
+/// `key 811589153, 10 rounds, offsets 1000,2000,3000`
+impl Display for Decryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let offsets: Vec<String> = self.offsets.iter().map(usize::to_string).collect();
+        write!(
+            f,
+            "key {}, {} rounds, offsets {}",
+            self.key,
+            self.rounds,
+            offsets.join(",")
+        )
+    }
+}
+
+/// `values` after moving every one of them along the circle by its value, in the order they
+/// started in, `rounds` times over. `moved` is called after every move, for progress bars
+pub fn mix(values: &[isize], rounds: usize, moved: impl FnMut()) -> Vec<isize> {
+    let mut nums: Vec<Num> = values
+        .iter()
+        .enumerate()
+        .map(|(index, &value)| Num { value, index })
+        .collect();
+    mix_nums(&mut nums, rounds, moved);
+
+    nums.into_iter().map(|n| n.value).collect()
+}
+
+/// the sum of the numbers `offsets` after the 0, wrapping around, or `None` if there's no 0
+pub fn gps(values: &[isize], offsets: &[usize]) -> Option<isize> {
+    let zero_val = values.iter().position(|&v| v == 0)?;
+
+    let sum = offsets
+        .iter()
+        .map(|i| values[(i + zero_val) % values.len()])
+        .sum();
+    Some(sum)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Num {
+    value: isize,
+    /// where the number started, which is when it gets its turn to move
+    index: usize,
+}
+
+fn mix_nums(nums: &mut Vec<Num>, rounds: usize, mut moved: impl FnMut()) {
+    // there's nowhere for a lone number to go
+    if nums.len() < 2 {
+        return;
+    }
+
+    let mut buckets = Buckets::new(nums);
+    for _ in 0..rounds {
+        for i in 0..nums.len() {
+            let (index, num) = buckets.remove(i);
+            let new_index = (index as isize + num.value).rem_euclid(nums.len() as isize - 1);
+            buckets.insert(new_index as usize, num);
+
+            moved();
+        }
+    }
+    *nums = buckets.into_vec();
+}
+
+/// the numbers in their current order, split into buckets of about √n of them so that finding,
+/// removing and inserting one never has to shift the whole list
+struct Buckets {
+    buckets: Vec<Vec<Num>>,
+    /// the bucket each number is in, by the index it started at
+    bucket_of: Vec<usize>,
+    size: usize,
+}
+
+impl Buckets {
+    fn new(nums: &[Num]) -> Self {
+        let mut buckets = Self {
+            buckets: vec![nums.to_vec()],
+            bucket_of: vec![0; nums.len()],
+            size: (nums.len() as f64).sqrt().ceil().max(1.0) as usize,
+        };
+        buckets.rebuild();
+        buckets
+    }
+
+    /// evens the buckets back out, once inserts have piled up in one of them
+    fn rebuild(&mut self) {
+        let nums: Vec<Num> = self.buckets.concat();
+        self.buckets = nums.chunks(self.size).map(<[Num]>::to_vec).collect();
+        for (b, bucket) in self.buckets.iter().enumerate() {
+            for num in bucket {
+                self.bucket_of[num.index] = b;
+            }
+        }
+    }
+
+    /// takes out the number that started at `index`, along with where it was
+    fn remove(&mut self, index: usize) -> (usize, Num) {
+        let b = self.bucket_of[index];
+        let offset = self.buckets[b]
+            .iter()
+            .position(|n| n.index == index)
+            .expect("the bucket a number is in always has it");
+        let before: usize = self.buckets[..b].iter().map(Vec::len).sum();
+
+        (before + offset, self.buckets[b].remove(offset))
+    }
+
+    fn insert(&mut self, mut at: usize, num: Num) {
+        let last = self.buckets.len() - 1;
+        for (b, bucket) in self.buckets.iter_mut().enumerate() {
+            if at <= bucket.len() || b == last {
+                bucket.insert(at, num);
+                self.bucket_of[num.index] = b;
+                if bucket.len() > 2 * self.size {
+                    self.rebuild();
+                }
+                return;
+            }
+            at -= bucket.len();
+        }
+    }
+
+    fn into_vec(self) -> Vec<Num> {
+        self.buckets.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [isize; 7] = [1, 2, -3, 3, -2, 0, 4];
+
+    fn nums(values: &[isize]) -> Vec<Num> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| Num { value, index })
+            .collect()
+    }
+
+    #[test]
+    fn test_mix() {
+        // the puzzle's 1, 2, -3, 4, 0, 3, -2, starting from a different point on the circle
+        assert_eq!(mix(&EXAMPLE, 1, || {}), vec![-2, 1, 2, -3, 4, 0, 3]);
+        assert_eq!(mix(&[1, 1, 0, -1, 1], 1, || {}), vec![1, 1, 1, -1, 0]);
+        assert_eq!(mix(&[7], 3, || {}), vec![7]);
+
+        let mut moves = 0;
+        mix(&EXAMPLE, 3, || moves += 1);
+        assert_eq!(moves, 21);
+    }
+
+    #[test]
+    fn test_large_values() {
+        // with the moving number taken out there are 3 places, so 9 moves like 0 and -7 like -1
+        let mut large = nums(&[0, 9, -7, 3]);
+        mix_nums(&mut large, 1, || {});
+        assert_eq!(
+            large.iter().map(|n| n.value).collect::<Vec<_>>(),
+            [3, 0, -7, 9]
+        );
+
+        let mut small = nums(&[0, 0, -1, 0]);
+        mix_nums(&mut small, 1, || {});
+        let order = |nums: &[Num]| nums.iter().map(|n| n.index).collect::<Vec<_>>();
+        assert_eq!(order(&large), order(&small));
+    }
+
+    #[test]
+    fn test_decryption() {
+        assert_eq!(Decryption::part_one().decrypt(&EXAMPLE, || {}), Some(3));
+        assert_eq!(
+            Decryption::part_two().decrypt(&EXAMPLE, || {}),
+            Some(1623178306)
+        );
+
+        // after one round the file is 1, 2, -3, 4, 0, 3, -2
+        let decryption = Decryption {
+            offsets: vec![1, 2, 3],
+            ..Decryption::part_one()
+        };
+        assert_eq!(decryption.decrypt(&EXAMPLE, || {}), Some(3 - 2 + 1));
+
+        let decryption = Decryption {
+            key: 2,
+            rounds: 0,
+            offsets: vec![1],
+        };
+        assert_eq!(decryption.decrypt(&EXAMPLE, || {}), Some(8));
+        assert_eq!(decryption.to_string(), "key 2, 0 rounds, offsets 1");
+        assert_eq!(
+            Decryption::part_two().to_string(),
+            "key 811589153, 10 rounds, offsets 1000,2000,3000"
+        );
+
+        assert_eq!(Decryption::part_two().decrypt(&[0], || {}), Some(0));
+        assert_eq!(Decryption::part_one().decrypt(&[7], || {}), None);
+        assert_eq!(gps(&[], &[1]), None);
+    }
+
+    /// the straightforward way, moving every number in a plain vec
+    fn naive_mix(nums: &mut Vec<Num>, rounds: usize) {
+        for _ in 0..rounds {
+            for i in 0..nums.len() {
+                let index = nums.iter().position(|n| n.index == i).unwrap();
+                let num = nums.remove(index);
+                let new_index = (index as isize + num.value).rem_euclid(nums.len() as isize);
+                nums.insert(new_index as usize, num);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mix_matches_naive() {
+        // a small xorshift, so the lists are random but the same every run
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..200 {
+            let len = 2 + random() as usize % 60;
+            let spread = 1 + random() as isize % 200;
+            let values: Vec<isize> = (0..len).map(|_| random() as isize % spread).collect();
+            let rounds = 1 + random() as usize % 3;
+
+            let mut fast = nums(&values);
+            mix_nums(&mut fast, rounds, || {});
+            let mut naive = nums(&values);
+            naive_mix(&mut naive, rounds);
+
+            assert_eq!(fast, naive);
+        }
+    }
+}
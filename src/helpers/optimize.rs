@@ -0,0 +1,422 @@
+//! branch and bound, for "make the best decisions before time runs out" puzzles.
+//!
+//! a [`Problem`] says what can be done from a state and how good a state is, and the
+//! [`Optimizer`] searches for the best sequence of decisions, skipping any branch whose
+//! optimistic [`Problem::bound`] can't beat the best score found so far.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+    sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
+};
+
+use rayon::prelude::*;
+
+pub trait Problem {
+    type State: Clone;
+    type Decision: Clone;
+    /// what [`Problem::key`] groups states by. `()` if there's no dedup
+    type Key: Hash + Eq;
+
+    /// every decision that can be made from `state`, with the state it leads to
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Decision, Self::State)>;
+
+    /// the score of `state` if no more decisions are made
+    fn score(&self, state: &Self::State) -> u64;
+
+    /// an optimistic guess of the best score reachable from `state`. it must never be lower
+    /// than the real one, and the closer it is the more gets pruned
+    fn bound(&self, state: &Self::State) -> u64;
+
+    /// states with the same key are compared with [`Problem::dominates`], and dropped if an
+    /// earlier one is at least as good. `None` opts a state out
+    fn key(&self, _state: &Self::State) -> Option<Self::Key> {
+        None
+    }
+
+    /// whether `a` can always do at least as well as `b`. only called for states with the same
+    /// key, so by default they're considered duplicates
+    fn dominates(&self, _a: &Self::State, _b: &Self::State) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// goes deep first, trying the most promising decisions first. cheap on memory
+    #[default]
+    DepthFirst,
+    /// always expands the state with the best bound, and stops as soon as nothing left can beat
+    /// the best score. keeps every open state in memory
+    BestFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<D> {
+    pub score: u64,
+    /// the decisions leading from the initial state to the best one
+    pub decisions: Vec<D>,
+    /// how many states were expanded
+    pub explored: usize,
+}
+
+pub struct Optimizer<'a, P> {
+    problem: &'a P,
+    strategy: Strategy,
+}
+
+impl<'a, P: Problem> Optimizer<'a, P> {
+    pub fn new(problem: &'a P) -> Self {
+        Self {
+            problem,
+            strategy: Strategy::default(),
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn solve(&self, initial: P::State) -> Solution<P::Decision> {
+        let best = AtomicU64::new(0);
+        Search::new(self.problem, &best).run(self.strategy, initial, Vec::new())
+    }
+
+    /// like [`Optimizer::solve`], but searches the branches after the first decision in parallel.
+    /// they share the best score for pruning, but not their dedup tables
+    pub fn par_solve(&self, initial: P::State) -> Solution<P::Decision>
+    where
+        P: Sync,
+        P::State: Send,
+        P::Decision: Send,
+    {
+        let best = AtomicU64::new(self.problem.score(&initial));
+        let here = Solution {
+            score: self.problem.score(&initial),
+            decisions: Vec::new(),
+            explored: 1,
+        };
+
+        // the better of two solutions, counting the states both explored
+        fn better<D>(a: Solution<D>, b: Solution<D>) -> Solution<D> {
+            let explored = a.explored + b.explored;
+            let best = if b.score > a.score { b } else { a };
+            Solution { explored, ..best }
+        }
+
+        let branches = self
+            .problem
+            .successors(&initial)
+            .into_par_iter()
+            .map(|(decision, state)| {
+                Search::new(self.problem, &best).run(self.strategy, state, vec![decision])
+            })
+            .reduce_with(better);
+
+        match branches {
+            Some(branches) => better(here, branches),
+            None => here,
+        }
+    }
+}
+
+/// one search through part of the tree
+struct Search<'a, P: Problem> {
+    problem: &'a P,
+    /// the best score found by any search, for pruning
+    shared_best: &'a AtomicU64,
+    best: Option<(u64, Vec<P::Decision>)>,
+    seen: HashMap<P::Key, Vec<P::State>>,
+    explored: usize,
+}
+
+impl<'a, P: Problem> Search<'a, P> {
+    fn new(problem: &'a P, shared_best: &'a AtomicU64) -> Self {
+        Self {
+            problem,
+            shared_best,
+            best: None,
+            seen: HashMap::new(),
+            explored: 0,
+        }
+    }
+
+    fn run(
+        mut self,
+        strategy: Strategy,
+        initial: P::State,
+        mut path: Vec<P::Decision>,
+    ) -> Solution<P::Decision> {
+        match strategy {
+            Strategy::DepthFirst => self.depth_first(&initial, &mut path),
+            Strategy::BestFirst => self.best_first(initial, path),
+        }
+
+        let (score, decisions) = self.best.expect("the initial state always has a score");
+        Solution {
+            score,
+            decisions,
+            explored: self.explored,
+        }
+    }
+
+    /// records `state` as the best so far if it is
+    fn visit(&mut self, state: &P::State, path: impl FnOnce() -> Vec<P::Decision>) {
+        self.explored += 1;
+        let score = self.problem.score(state);
+        if self.best.as_ref().is_none_or(|(best, _)| score > *best) {
+            self.best = Some((score, path()));
+            self.shared_best.fetch_max(score, AtomicOrdering::Relaxed);
+        }
+    }
+
+    fn can_beat_best(&self, bound: u64) -> bool {
+        bound > self.shared_best.load(AtomicOrdering::Relaxed)
+    }
+
+    /// whether `state` is worth expanding, remembering it if so
+    fn admit(&mut self, state: &P::State) -> bool {
+        let Some(key) = self.problem.key(state) else {
+            return true;
+        };
+
+        let seen = self.seen.entry(key).or_default();
+        if seen.iter().any(|s| self.problem.dominates(s, state)) {
+            return false;
+        }
+        seen.retain(|s| !self.problem.dominates(state, s));
+        seen.push(state.clone());
+        true
+    }
+
+    /// the successors that could still beat the best score, most promising first
+    fn branches(&mut self, state: &P::State) -> Vec<(u64, P::Decision, P::State)> {
+        let mut branches: Vec<_> = self
+            .problem
+            .successors(state)
+            .into_iter()
+            .map(|(decision, next)| (self.problem.bound(&next), decision, next))
+            .filter(|(bound, _, _)| self.can_beat_best(*bound))
+            .collect();
+        branches.retain(|(_, _, next)| self.admit(next));
+        branches.sort_by_key(|(bound, _, _)| Reverse(*bound));
+        branches
+    }
+
+    fn depth_first(&mut self, state: &P::State, path: &mut Vec<P::Decision>) {
+        self.visit(state, || path.clone());
+
+        for (bound, decision, next) in self.branches(state) {
+            // the best might have improved since the branches were worked out
+            if !self.can_beat_best(bound) {
+                continue;
+            }
+            path.push(decision);
+            self.depth_first(&next, path);
+            path.pop();
+        }
+    }
+
+    fn best_first(&mut self, initial: P::State, path: Vec<P::Decision>) {
+        // every state in the heap keeps its decision here, pointing back at its parent's
+        let mut decisions: Vec<(Option<usize>, P::Decision)> = Vec::new();
+        let path_to = |decisions: &[(Option<usize>, P::Decision)], mut node: Option<usize>| {
+            let mut tail = Vec::new();
+            while let Some(n) = node {
+                tail.push(decisions[n].1.clone());
+                node = decisions[n].0;
+            }
+            tail.reverse();
+            [path.clone(), tail].concat()
+        };
+
+        let mut heap = BinaryHeap::new();
+        heap.push(Open {
+            bound: self.problem.bound(&initial),
+            node: None,
+            state: initial,
+        });
+
+        while let Some(Open { bound, node, state }) = heap.pop() {
+            // nothing left in the heap has a better bound, so nothing can beat the best
+            if self.best.is_some() && !self.can_beat_best(bound) {
+                break;
+            }
+
+            self.visit(&state, || path_to(&decisions, node));
+            for (bound, decision, next) in self.branches(&state) {
+                decisions.push((node, decision));
+                heap.push(Open {
+                    bound,
+                    node: Some(decisions.len() - 1),
+                    state: next,
+                });
+            }
+        }
+    }
+}
+
+/// a state waiting in the best first heap, ordered by its bound
+struct Open<S> {
+    bound: u64,
+    node: Option<usize>,
+    state: S,
+}
+
+impl<S> PartialEq for Open<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound == other.bound
+    }
+}
+impl<S> Eq for Open<S> {}
+
+impl<S> Ord for Open<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.cmp(&other.bound)
+    }
+}
+impl<S> PartialOrd for Open<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0/1 knapsack: decide for each item in turn whether to take it
+    struct Knapsack {
+        /// (weight, value)
+        items: Vec<(u64, u64)>,
+        capacity: u64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Packed {
+        next: usize,
+        weight: u64,
+        value: u64,
+    }
+
+    impl Problem for Knapsack {
+        type State = Packed;
+        type Decision = bool;
+        type Key = (usize, u64);
+
+        fn successors(&self, state: &Packed) -> Vec<(bool, Packed)> {
+            let Some(&(weight, value)) = self.items.get(state.next) else {
+                return Vec::new();
+            };
+
+            let skip = Packed {
+                next: state.next + 1,
+                ..*state
+            };
+            let mut successors = vec![(false, skip)];
+            if state.weight + weight <= self.capacity {
+                let take = Packed {
+                    next: state.next + 1,
+                    weight: state.weight + weight,
+                    value: state.value + value,
+                };
+                successors.push((true, take));
+            }
+            successors
+        }
+
+        fn score(&self, state: &Packed) -> u64 {
+            state.value
+        }
+
+        fn bound(&self, state: &Packed) -> u64 {
+            let rest: u64 = self.items[state.next..].iter().map(|(_, v)| v).sum();
+            state.value + rest
+        }
+
+        fn key(&self, state: &Packed) -> Option<(usize, u64)> {
+            Some((state.next, state.weight))
+        }
+
+        fn dominates(&self, a: &Packed, b: &Packed) -> bool {
+            a.value >= b.value
+        }
+    }
+
+    fn knapsack() -> Knapsack {
+        Knapsack {
+            items: vec![
+                (12, 4),
+                (2, 2),
+                (1, 1),
+                (1, 2),
+                (4, 10),
+                (7, 6),
+                (3, 5),
+                (5, 5),
+            ],
+            capacity: 15,
+        }
+    }
+
+    fn brute_force(knapsack: &Knapsack) -> u64 {
+        (0..1u32 << knapsack.items.len())
+            .filter_map(|taken| {
+                let (weight, value) = knapsack
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| taken & (1 << i) != 0)
+                    .fold((0, 0), |(w, v), (_, (iw, iv))| (w + iw, v + iv));
+                (weight <= knapsack.capacity).then_some(value)
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn check(knapsack: &Knapsack, solution: &Solution<bool>) {
+        assert_eq!(solution.score, brute_force(knapsack));
+
+        let (weight, value) = knapsack
+            .items
+            .iter()
+            .zip(solution.decisions.iter())
+            .filter(|(_, &taken)| taken)
+            .fold((0, 0), |(w, v), ((iw, iv), _)| (w + iw, v + iv));
+        assert!(weight <= knapsack.capacity);
+        assert_eq!(value, solution.score);
+    }
+
+    #[test]
+    fn test_strategies() {
+        let knapsack = knapsack();
+        let initial = Packed {
+            next: 0,
+            weight: 0,
+            value: 0,
+        };
+        let total = (1 << (knapsack.items.len() + 1)) - 1;
+
+        let depth_first = Optimizer::new(&knapsack).solve(initial.clone());
+        check(&knapsack, &depth_first);
+        assert!(depth_first.explored < total);
+
+        let best_first = Optimizer::new(&knapsack)
+            .with_strategy(Strategy::BestFirst)
+            .solve(initial.clone());
+        check(&knapsack, &best_first);
+        assert!(best_first.explored < total);
+
+        let parallel = Optimizer::new(&knapsack).par_solve(initial.clone());
+        check(&knapsack, &parallel);
+
+        let empty = Knapsack {
+            items: Vec::new(),
+            capacity: 3,
+        };
+        let solution = Optimizer::new(&empty).solve(initial);
+        assert_eq!((solution.score, solution.decisions), (0, vec![]));
+    }
+}
@@ -0,0 +1,195 @@
+//! small nom parsers that keep coming up in puzzle inputs, and a way to run them that reports
+//! where the input went wrong instead of panicking.
+
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    bytes::complete::take_till1,
+    character::complete::{char, digit1, not_line_ending, one_of, space0},
+    combinator::{all_consuming, map_res, opt, recognize},
+    multi::separated_list1,
+    sequence::{pair, separated_pair},
+    Finish, IResult,
+};
+
+/// where and why some input failed to parse. lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// an error at the start of `rest`, which has to be the tail end of `input`
+    pub fn at(input: &str, rest: &str, message: impl Into<String>) -> Self {
+        let offset = input.len() - rest.len();
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// runs `parser` over the whole of `input`, ignoring trailing whitespace. anything left over is
+/// an error
+pub fn run<'a, T>(
+    input: &'a str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, ParseError> {
+    let input = input.trim_end();
+    all_consuming(parser)(input)
+        .finish()
+        .map(|(_, t)| t)
+        .map_err(|e| ParseError::at(input, e.input, format!("expected {}", e.code.description())))
+}
+
+/// runs `parser` over every line of `input`, stopping at the first one that fails
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| run(line, &mut parser).map_err(|e| ParseError { line: i + 1, ..e }))
+        .collect()
+}
+
+/// a number with an optional `+` or `-` in front
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// a number without a sign
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// at least one `item`, separated by commas and optional spaces, like `1, 2,3`
+pub fn comma_list<'a, T>(
+    item: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    separated_list1(pair(char(','), space0), item)
+}
+
+/// a `key: value` line. the value is the rest of the line, and can be empty
+pub fn key_value(input: &str) -> IResult<&str, (&str, &str)> {
+    separated_pair(
+        take_till1(|c| c == ':' || c == '\n'),
+        pair(char(':'), space0),
+        not_line_ending,
+    )(input)
+}
+
+/// the chunks of `input` separated by blank lines, without their surrounding newlines
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|block| block.trim_matches('\n'))
+        .filter(|block| !block.is_empty())
+}
+
+/// every integer in `line`, in order, ignoring whatever is around them. a `-` right before the
+/// digits makes it negative if `T` can be, and is skipped like any other separator if it can't,
+/// so `2-4,6-8` is `[2, 4, 6, 8]` as `u32` but `[2, -4, 6, -8]` as `i32`
+pub fn integers<T: FromStr>(line: &str) -> Vec<T> {
+    let mut numbers = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let negative = rest[..start].ends_with('-');
+        let len = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - start);
+        let digits = &rest[start..start + len];
+        let signed = &rest[start - negative as usize..start + len];
+
+        if let Ok(n) = signed.parse().or_else(|_| digits.parse()) {
+            numbers.push(n);
+        }
+        rest = &rest[start + len..];
+    }
+
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(run("-12", signed), Ok(-12));
+        assert_eq!(run("+3", signed::<i32>), Ok(3));
+        assert_eq!(run("42", unsigned::<u8>), Ok(42));
+        assert!(run("300", unsigned::<u8>).is_err());
+        assert!(run("-1", unsigned::<u8>).is_err());
+
+        assert_eq!(run("1, -2,3", comma_list(signed)), Ok(vec![1, -2, 3]));
+        assert_eq!(
+            integers::<i64>("Sensor at x=2, y=-18: beacon at x=-2, y=15"),
+            vec![2, -18, -2, 15]
+        );
+        assert_eq!(integers::<u32>("move 10 from 2 to 13"), vec![10, 2, 13]);
+        assert!(integers::<u32>("no numbers").is_empty());
+
+        assert_eq!(integers::<u32>("2-4,6-8"), vec![2, 4, 6, 8]);
+        assert_eq!(integers::<i32>("2-4,6-8"), vec![2, -4, 6, -8]);
+        assert_eq!(integers::<usize>("x=-3"), vec![3]);
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(
+            run("root: pppw + sjmn", key_value),
+            Ok(("root", "pppw + sjmn"))
+        );
+        assert_eq!(run("dbpl:5", key_value), Ok(("dbpl", "5")));
+        assert!(run(": 5", key_value).is_err());
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+        assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = run("1,2,x", comma_list(signed::<i32>)).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 4));
+        // inputs from read_file end in a newline, which mustn't move the column
+        for input in ["1,2,x\n", "1,2,x   \n\n"] {
+            let error = run(input, comma_list(signed::<i32>)).unwrap_err();
+            assert_eq!((error.line, error.column), (1, 4));
+        }
+
+        let error = lines("1,2\n3\n\n4,,5", comma_list(signed::<i32>)).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 2));
+        assert_eq!(
+            error.to_string(),
+            format!("line 4, column 2: {}", error.message)
+        );
+
+        assert_eq!(
+            lines("1,2\n3\n", comma_list(signed::<i32>)),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use super::Direction;
+
+/// a point on a 2d grid. `y` grows downwards, so [`Direction::Up`] is `y - 1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+    pub fn from_x_y((x, y): (isize, isize)) -> Self {
+        Self { x, y }
+    }
+
+    /// wraps the point back into the half open ranges `x_min..x_max` and `y_min..y_max`, however
+    /// many periods outside of them it is. `x_max` and `y_max` themselves are outside, so a point
+    /// at `x_max` ends up at `x_min`. a coordinate is left alone if its range is empty, since
+    /// there's nowhere in it to wrap to
+    pub fn ensure_between(
+        &mut self,
+        (x_min, x_max): (isize, isize),
+        (y_min, y_max): (isize, isize),
+    ) {
+        if x_min < x_max {
+            self.x = x_min + (self.x - x_min).rem_euclid(x_max - x_min);
+        }
+        if y_min < y_max {
+            self.y = y_min + (self.y - y_min).rem_euclid(y_max - y_min);
+        }
+    }
+
+    pub fn manhattan_distance_to(&self, other: &Self) -> usize {
+        ((self.x - other.x).abs() + (self.y - other.y).abs()) as usize
+    }
+
+    /// the point one step away in `direction`
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// each coordinate clamped to -1, 0 or 1
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Self::Output {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Self::Output {
+        self.step(direction)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Self::Output {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, n: isize) -> Self::Output {
+        Point {
+            x: self.x * n,
+            y: self.y * n,
+        }
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from(p: (isize, isize)) -> Self {
+        Self::from_x_y(p)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a + b, Point::new(-2, 7));
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(b * 2, Point::new(-6, 10));
+        assert_eq!(-a, Point::new(-1, -2));
+        assert_eq!((a - b).signum(), Point::new(1, -1));
+        assert_eq!(a + Direction::Up, Point::new(1, 1));
+        assert_eq!(a + Direction::DownLeft.offset() * 3, Point::new(-2, 5));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c += Direction::Right;
+        assert_eq!(c, Point::new(-2, 5));
+    }
+
+    #[test]
+    fn test_ensure_between() {
+        let mut p = Point::new(-13, 27);
+        p.ensure_between((0, 5), (0, 5));
+        assert_eq!(p, Point::new(2, 2));
+
+        let mut p = Point::new(5, -1);
+        p.ensure_between((1, 5), (-2, 3));
+        assert_eq!(p, Point::new(1, -1));
+
+        // the max is excluded, the one before it isn't
+        let mut p = Point::new(4, 3);
+        p.ensure_between((0, 5), (0, 3));
+        assert_eq!(p, Point::new(4, 0));
+
+        // nowhere to wrap to, so the point stays put
+        let mut p = Point::new(7, -2);
+        p.ensure_between((0, 0), (3, 3));
+        assert_eq!(p, Point::new(7, -2));
+    }
+}
@@ -0,0 +1,285 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// a point with `D` coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<const D: usize>(pub [isize; D]);
+
+pub type Point3 = PointN<3>;
+
+impl Point3 {
+    pub const fn new(x: isize, y: isize, z: isize) -> Self {
+        Self([x, y, z])
+    }
+    pub fn x(&self) -> isize {
+        self.0[0]
+    }
+    pub fn y(&self) -> isize {
+        self.0[1]
+    }
+    pub fn z(&self) -> isize {
+        self.0[2]
+    }
+}
+
+impl<const D: usize> PointN<D> {
+    pub const ORIGIN: Self = Self([0; D]);
+
+    /// a point with every coordinate set to `n`
+    pub const fn splat(n: isize) -> Self {
+        Self([n; D])
+    }
+
+    pub fn manhattan_distance_to(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .sum()
+    }
+
+    /// whether every coordinate is between the ones of `min` and `max`, both included
+    pub fn is_within(&self, min: &Self, max: &Self) -> bool {
+        (0..D).all(|i| min.0[i] <= self.0[i] && self.0[i] <= max.0[i])
+    }
+
+    /// points that share a face with this one, ie. differ by 1 in a single coordinate. there
+    /// are `2 * D` of them
+    pub fn faces(self) -> impl Iterator<Item = Self> {
+        self.neighbors_with(1)
+    }
+
+    /// points that share only an edge with this one, ie. differ by 1 in exactly two coordinates
+    pub fn edges(self) -> impl Iterator<Item = Self> {
+        self.neighbors_with(2)
+    }
+
+    /// points that share only a corner with this one, ie. differ by 1 in every coordinate
+    pub fn corners(self) -> impl Iterator<Item = Self> {
+        self.neighbors_with(D)
+    }
+
+    /// every point touching this one, `3^D - 1` of them
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        offsets::<D>()
+            .filter(|offset| *offset != Self::ORIGIN)
+            .map(move |offset| self + offset)
+    }
+
+    fn neighbors_with(self, changed: usize) -> impl Iterator<Item = Self> {
+        offsets::<D>()
+            .filter(move |offset| offset.0.iter().filter(|&&n| n != 0).count() == changed)
+            .map(move |offset| self + offset)
+    }
+
+    /// the smallest and largest coordinates over all the points, or `None` if there are none
+    pub fn bounds<'a>(points: impl IntoIterator<Item = &'a Self>) -> Option<(Self, Self)> {
+        points.into_iter().fold(None, |bounds, p| {
+            let (mut min, mut max) = bounds.unwrap_or((*p, *p));
+            for i in 0..D {
+                min.0[i] = min.0[i].min(p.0[i]);
+                max.0[i] = max.0[i].max(p.0[i]);
+            }
+            Some((min, max))
+        })
+    }
+}
+
+/// every offset with coordinates in -1..=1, in lexicographic order
+fn offsets<const D: usize>() -> impl Iterator<Item = PointN<D>> {
+    (0..3usize.pow(D as u32)).map(|mut n| {
+        let mut offset = [0; D];
+        for coord in offset.iter_mut().rev() {
+            *coord = (n % 3) as isize - 1;
+            n /= 3;
+        }
+        PointN(offset)
+    })
+}
+
+impl<const D: usize> Default for PointN<D> {
+    fn default() -> Self {
+        Self::ORIGIN
+    }
+}
+
+impl<const D: usize> Add for PointN<D> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+impl<const D: usize> AddAssign for PointN<D> {
+    fn add_assign(&mut self, other: Self) {
+        for i in 0..D {
+            self.0[i] += other.0[i];
+        }
+    }
+}
+
+impl<const D: usize> Sub for PointN<D> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= other;
+        self
+    }
+}
+
+impl<const D: usize> SubAssign for PointN<D> {
+    fn sub_assign(&mut self, other: Self) {
+        for i in 0..D {
+            self.0[i] -= other.0[i];
+        }
+    }
+}
+
+impl<const D: usize> Mul<isize> for PointN<D> {
+    type Output = Self;
+
+    fn mul(self, n: isize) -> Self::Output {
+        Self(self.0.map(|c| c * n))
+    }
+}
+
+impl<const D: usize> Neg for PointN<D> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|c| -c))
+    }
+}
+
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = isize;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<const D: usize> IndexMut<usize> for PointN<D> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<const D: usize> From<[isize; D]> for PointN<D> {
+    fn from(coords: [isize; D]) -> Self {
+        Self(coords)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePointError(pub String);
+
+impl Display for ParsePointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid point {:?}", self.0)
+    }
+}
+
+/// parses comma separated coordinates, like `1,-2,3`
+impl<const D: usize> FromStr for PointN<D> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParsePointError(s.to_string());
+        let mut coords = [0; D];
+        let mut parts = s.split(',');
+
+        for coord in coords.iter_mut() {
+            let part = parts.next().ok_or_else(err)?;
+            *coord = part.trim().parse().map_err(|_| err())?;
+        }
+        if parts.next().is_some() {
+            return Err(err());
+        }
+
+        Ok(Self(coords))
+    }
+}
+
+impl<const D: usize> Display for PointN<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(-1, 0, 5);
+        assert_eq!(a + b, Point3::new(0, 2, 8));
+        assert_eq!(a - b, Point3::new(2, 2, -2));
+        assert_eq!(a * 3, Point3::new(3, 6, 9));
+        assert_eq!(-a, Point3::new(-1, -2, -3));
+        assert_eq!(a.manhattan_distance_to(&b), 6);
+        assert_eq!((a.x(), a.y(), a.z()), (1, 2, 3));
+        assert_eq!(a.to_string(), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point3::new(0, 0, 0);
+        assert_eq!(p.faces().count(), 6);
+        assert_eq!(p.edges().count(), 12);
+        assert_eq!(p.corners().count(), 8);
+        assert_eq!(p.neighbors().count(), 26);
+        assert!(p.faces().all(|f| f.manhattan_distance_to(&p) == 1));
+        assert!(p.corners().all(|c| c.manhattan_distance_to(&p) == 3));
+
+        let p = PointN([5, 5]);
+        assert_eq!(
+            p.faces().collect::<Vec<_>>(),
+            vec![
+                PointN([4, 5]),
+                PointN([5, 4]),
+                PointN([5, 6]),
+                PointN([6, 5])
+            ]
+        );
+        assert_eq!(PointN([0; 4]).neighbors().count(), 80);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let points = [
+            Point3::new(1, 5, -2),
+            Point3::new(3, -1, 0),
+            Point3::new(2, 2, 2),
+        ];
+        let (min, max) = Point3::bounds(&points).unwrap();
+        assert_eq!(min, Point3::new(1, -1, -2));
+        assert_eq!(max, Point3::new(3, 5, 2));
+        assert!(points.iter().all(|p| p.is_within(&min, &max)));
+        assert!(!Point3::new(0, 0, 0).is_within(&min, &max));
+        assert_eq!(Point3::bounds(&[]), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1,-2,3".parse(), Ok(Point3::new(1, -2, 3)));
+        assert_eq!("4, 5".parse(), Ok(PointN([4, 5])));
+        assert!("1,2".parse::<Point3>().is_err());
+        assert!("1,2,3,4".parse::<Point3>().is_err());
+        assert!("1,a,3".parse::<Point3>().is_err());
+    }
+}
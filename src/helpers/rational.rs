@@ -0,0 +1,278 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// an exact fraction, always kept in lowest terms with a positive denominator, so equal values
+/// compare and hash equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { numer: 0, denom: 1 };
+    pub const ONE: Self = Self { numer: 1, denom: 1 };
+
+    /// `numer / denom`, or `None` if `denom` is 0. also `None` in the one case where the
+    /// denominator can't be made positive: an odd `numer` over `i128::MIN`
+    pub fn new(numer: i128, denom: i128) -> Option<Self> {
+        if denom == 0 {
+            return None;
+        }
+
+        let Ok(gcd) = i128::try_from(gcd(numer, denom)) else {
+            // only 2^127 doesn't fit, when both are 0 or i128::MIN
+            return Some(if numer == 0 { Self::ZERO } else { Self::ONE });
+        };
+        let (numer, denom) = (numer / gcd, denom / gcd);
+        Some(match denom < 0 {
+            true => Self {
+                numer: numer.checked_neg()?,
+                denom: denom.checked_neg()?,
+            },
+            false => Self { numer, denom },
+        })
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    /// the value if it's a whole number
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    /// `None` if the numerator or denominator would overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let numer = self
+            .numer
+            .checked_mul(other.denom)?
+            .checked_add(other.numer.checked_mul(self.denom)?)?;
+        Self::new(numer, self.denom.checked_mul(other.denom)?)
+    }
+
+    /// `None` if the numerator or denominator would overflow
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    /// `None` if the numerator or denominator would overflow
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numer.checked_mul(other.numer)?,
+            self.denom.checked_mul(other.denom)?,
+        )
+    }
+
+    /// `None` when dividing by 0, or if the numerator or denominator would overflow
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numer.checked_mul(other.denom)?,
+            self.denom.checked_mul(other.numer)?,
+        )
+    }
+
+    /// `None` if the numerator is `i128::MIN`
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numer: self.numer.checked_neg()?,
+            denom: self.denom,
+        })
+    }
+}
+
+fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self { numer: n, denom: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::from(n as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    /// panics on overflow, in release builds too. see [`Rational::checked_add`]
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .expect("attempt to add with overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    /// panics on overflow, in release builds too. see [`Rational::checked_sub`]
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    /// panics on overflow, in release builds too. see [`Rational::checked_mul`]
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// panics when dividing by 0 or on overflow, like the integers do. see
+    /// [`Rational::checked_div`]
+    fn div(self, other: Self) -> Self {
+        assert!(!other.is_zero(), "attempt to divide by zero");
+        self.checked_div(other)
+            .expect("attempt to divide with overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    /// panics on overflow, in release builds too. see [`Rational::checked_neg`]
+    fn neg(self) -> Self {
+        self.checked_neg().expect("attempt to negate with overflow")
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // both denominators are positive, so cross multiplying keeps the order
+        match (
+            self.numer.checked_mul(other.denom),
+            other.numer.checked_mul(self.denom),
+        ) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            _ => cmp_fractions((self.numer, self.denom), (other.numer, other.denom)),
+        }
+    }
+}
+
+/// compares `a / b` with `c / d` without multiplying, by comparing the whole parts and then the
+/// flipped remainders, like a continued fraction. `b` and `d` have to be positive
+fn cmp_fractions((a, b): (i128, i128), (c, d): (i128, i128)) -> Ordering {
+    let whole = a.div_euclid(b).cmp(&c.div_euclid(d));
+    let (left, right) = (a.rem_euclid(b), c.rem_euclid(d));
+    match (whole, left, right) {
+        (Ordering::Equal, 0, _) | (Ordering::Equal, _, 0) => left.cmp(&right),
+        // a smaller remainder flips into a bigger fraction
+        (Ordering::Equal, _, _) => cmp_fractions((d, right), (b, left)),
+        (whole, _, _) => whole,
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom).unwrap()
+    }
+
+    #[test]
+    fn test_lowest_terms() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(3, -6), r(-1, 2));
+        assert_eq!(r(-3, -6).denom(), 2);
+        assert_eq!(r(0, -5), Rational::ZERO);
+        assert_eq!(Rational::new(1, 0), None);
+
+        assert_eq!(Rational::new(1, i128::MIN), None);
+        assert_eq!(Rational::new(2, i128::MIN), Some(r(-1, 1 << 126)));
+        assert_eq!(Rational::new(i128::MIN, i128::MIN), Some(Rational::ONE));
+        assert_eq!(Rational::new(0, i128::MIN), Some(Rational::ZERO));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(3, 4), r(-1, 4));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(-4, 3), r(-1, 2));
+        assert_eq!(r(1, 2).checked_div(Rational::ZERO), None);
+        assert!(r(-1, 2) < r(1, 3));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = Rational::from(i128::MAX);
+        assert_eq!(big.checked_add(Rational::ONE), None);
+        assert_eq!((-big).checked_sub(r(2, 1)), None);
+        assert_eq!(big.checked_mul(r(2, 1)), None);
+        assert_eq!(r(1, 2).checked_add(r(1, i128::MAX)), None);
+        assert_eq!(r(1, i128::MAX).checked_div(r(2, 1)), None);
+        assert_eq!(Rational::from(i128::MIN).checked_neg(), None);
+
+        assert_eq!(big.checked_add(-Rational::ONE), Some(r(i128::MAX - 1, 1)));
+        assert_eq!(big.checked_mul(r(1, 2)), Some(r(i128::MAX, 2)));
+
+        // cross multiplying these would overflow
+        assert!(r(i128::MAX, 3) > r(i128::MAX - 1, 3));
+        assert!(r(i128::MAX - 2, i128::MAX - 1) < r(i128::MAX - 1, i128::MAX));
+        assert!(r(-i128::MAX, 2) < r(i128::MAX, 7));
+        assert_eq!(r(i128::MAX, 5).cmp(&r(i128::MAX, 5)), Ordering::Equal);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to multiply with overflow")]
+    fn test_operator_overflow() {
+        let _ = Rational::from(i128::MAX) * r(3, 2);
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!((r(7, 2) * r(4, 1)).to_integer(), Some(14));
+        assert_eq!(r(7, 2).to_integer(), None);
+        assert_eq!(r(7, 2).to_string(), "7/2");
+        assert_eq!(Rational::from(-3i64).to_string(), "-3");
+    }
+}
@@ -0,0 +1,315 @@
+//! generic graph searches over a successor function.
+//!
+//! every search takes any number of start nodes, a `successors` closure, and a `success`
+//! predicate that stops the search as soon as a matching node is popped. the ones that find a
+//! path return the total cost together with the path from the start it came from, both ends
+//! included.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// breadth first search where every edge costs 1
+pub fn bfs<N, FN, IN, FS>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut tree = Tree::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if let Some(idx) = tree.insert_root(start, 0) {
+            queue.push_back(idx);
+        }
+    }
+
+    while let Some(idx) = queue.pop_front() {
+        if success(&tree.nodes[idx]) {
+            return Some((tree.costs[idx], tree.path_to(idx)));
+        }
+
+        let cost = tree.costs[idx] + 1;
+        for next in successors(&tree.nodes[idx]) {
+            if let Some(next) = tree.insert(next, idx, cost) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// depth first search. the path found is *a* path, not necessarily the shortest one, and the
+/// cost is its number of edges
+pub fn dfs<N, FN, IN, FS>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut tree = Tree::new();
+    let mut stack = Vec::new();
+
+    // reversed so the first start is the first one explored
+    let starts: Vec<_> = starts.into_iter().collect();
+    for start in starts.into_iter().rev() {
+        if let Some(idx) = tree.insert_root(start, 0) {
+            stack.push(idx);
+        }
+    }
+
+    while let Some(idx) = stack.pop() {
+        if success(&tree.nodes[idx]) {
+            return Some((tree.costs[idx], tree.path_to(idx)));
+        }
+
+        let cost = tree.costs[idx] + 1;
+        for next in successors(&tree.nodes[idx]) {
+            if let Some(next) = tree.insert(next, idx, cost) {
+                stack.push(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// every node reachable from `starts` (starts included), found with a depth first flood fill
+pub fn reachable<N, FN, IN>(starts: impl IntoIterator<Item = N>, mut successors: FN) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut stack = Vec::new();
+
+    for start in starts {
+        if seen.insert(start.clone()) {
+            stack.push(start);
+        }
+    }
+
+    while let Some(node) = stack.pop() {
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+/// dijkstra's algorithm. `successors` returns the neighbors of a node along with the cost of
+/// moving to each of them
+pub fn dijkstra<N, C, FN, IN, FS>(
+    starts: impl IntoIterator<Item = N>,
+    successors: FN,
+    success: FS,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::default(), success)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a successful node,
+/// otherwise the path returned might not be the cheapest one
+pub fn astar<N, C, FN, IN, FH, FS>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let mut tree = Tree::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if let Some(idx) = tree.insert_root(start, C::default()) {
+            let estimate = heuristic(&tree.nodes[idx]);
+            heap.push(Reverse((estimate, idx)));
+        }
+    }
+
+    while let Some(Reverse((estimate, idx))) = heap.pop() {
+        let cost = tree.costs[idx];
+        // a cheaper way to this node was found after this entry was pushed
+        if estimate > cost + heuristic(&tree.nodes[idx]) {
+            continue;
+        }
+        if success(&tree.nodes[idx]) {
+            return Some((cost, tree.path_to(idx)));
+        }
+
+        for (next, move_cost) in successors(&tree.nodes[idx]) {
+            let next_cost = cost + move_cost;
+            if let Some(next) = tree.relax(next, idx, next_cost) {
+                let estimate = next_cost + heuristic(&tree.nodes[next]);
+                heap.push(Reverse((estimate, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// every node seen so far, along with the cheapest known cost to it and where it came from
+struct Tree<N, C> {
+    nodes: Vec<N>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+    indices: HashMap<N, usize>,
+}
+
+impl<N, C> Tree<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord,
+{
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            costs: Vec::new(),
+            parents: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, node: N, parent: Option<usize>, cost: C) -> usize {
+        let idx = self.nodes.len();
+        self.indices.insert(node.clone(), idx);
+        self.nodes.push(node);
+        self.costs.push(cost);
+        self.parents.push(parent);
+        idx
+    }
+
+    fn insert_root(&mut self, node: N, cost: C) -> Option<usize> {
+        if self.indices.contains_key(&node) {
+            return None;
+        }
+        Some(self.push(node, None, cost))
+    }
+
+    /// inserts a node that hasn't been seen yet, returning `None` if it has
+    fn insert(&mut self, node: N, parent: usize, cost: C) -> Option<usize> {
+        if self.indices.contains_key(&node) {
+            return None;
+        }
+        Some(self.push(node, Some(parent), cost))
+    }
+
+    /// inserts a node, or updates it if `cost` is cheaper than what we had before
+    fn relax(&mut self, node: N, parent: usize, cost: C) -> Option<usize> {
+        match self.indices.get(&node) {
+            Some(&idx) if self.costs[idx] <= cost => None,
+            Some(&idx) => {
+                self.costs[idx] = cost;
+                self.parents[idx] = Some(parent);
+                Some(idx)
+            }
+            None => Some(self.push(node, Some(parent), cost)),
+        }
+    }
+
+    fn path_to(&self, mut idx: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[idx].clone()];
+        while let Some(parent) = self.parents[idx] {
+            path.push(self.nodes[parent].clone());
+            idx = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -1- 1 -1- 2 -1- 3
+    //  \_________10____/
+    fn line(n: &u32) -> Vec<(u32, u32)> {
+        match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(0, 1), (2, 1)],
+            2 => vec![(1, 1), (3, 1)],
+            3 => vec![(2, 1), (0, 10)],
+            _ => unreachable!(),
+        }
+    }
+
+    fn unweighted(n: &u32) -> Vec<u32> {
+        line(n).into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        assert_eq!(bfs([0], unweighted, |&n| n == 3), Some((1, vec![0, 3])));
+        assert_eq!(bfs([0], unweighted, |&n| n == 7), None);
+        // multiple starts
+        assert_eq!(bfs([0, 2], unweighted, |&n| n == 1), Some((1, vec![0, 1])));
+        assert_eq!(bfs([0, 2], unweighted, |&n| n == 2), Some((0, vec![2])));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let (cost, path) = dfs([0], unweighted, |&n| n == 2).unwrap();
+        assert_eq!(cost, path.len() - 1);
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&2));
+        assert_eq!(dfs([0], unweighted, |&n| n == 7), None);
+    }
+
+    #[test]
+    fn test_reachable() {
+        assert_eq!(reachable([0], unweighted), (0..4).collect());
+        assert_eq!(reachable([5], |_| vec![]), [5].into_iter().collect());
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(
+            dijkstra([0], line, |&n| n == 3),
+            Some((3, vec![0, 1, 2, 3]))
+        );
+        assert_eq!(dijkstra([3, 0], line, |&n| n == 1), Some((1, vec![0, 1])));
+    }
+
+    #[test]
+    fn test_astar() {
+        let heuristic = |&n: &u32| 3 - n;
+        assert_eq!(
+            astar([0], line, heuristic, |&n| n == 3),
+            Some((3, vec![0, 1, 2, 3]))
+        );
+    }
+}
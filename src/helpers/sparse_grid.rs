@@ -0,0 +1,287 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{Grid, Neighborhood, Point, Rect};
+
+/// an unbounded grid that only stores the cells that have been set. points can be negative, and
+/// the bounding box grows and shrinks with the cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Rect>,
+    empty: char,
+    flip_y: bool,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+            empty: '.',
+            flip_y: false,
+        }
+    }
+
+    /// the char printed for unset cells when displayed. defaults to `.`
+    pub fn with_empty(mut self, empty: char) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    /// display the highest y at the top instead of the lowest, for puzzles where y points up
+    pub fn with_flipped_y(mut self) -> Self {
+        self.flip_y = true;
+        self
+    }
+
+    /// whether the highest y is shown at the top
+    pub fn is_flipped_y(&self) -> bool {
+        self.flip_y
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// the smallest rectangle containing every set cell, or `None` if there aren't any
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, t: T) -> Option<T> {
+        self.bounds = Some(grow(self.bounds, p));
+        self.cells.insert(p, t)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        let removed = self.cells.remove(&p)?;
+
+        // only a cell on the edge can shrink the bounding box
+        if let Some(Rect { min, max }) = self.bounds {
+            if p.x == min.x || p.y == min.y || p.x == max.x || p.y == max.y {
+                self.bounds = bounds_of(self.cells.keys().copied());
+            }
+        }
+
+        Some(removed)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, t)| (*p, t))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// the set neighbors of `p`. wrapping neighborhoods don't wrap, since there's no edge
+    pub fn neighbors<'a>(
+        &'a self,
+        p: Point,
+        hood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        hood.around(p)
+            .filter_map(move |n| self.cells.get(&n).map(|t| (n, t)))
+    }
+
+    /// a dense grid covering the bounding box, with `fill` in every unset cell. the top left
+    /// corner of the bounding box becomes `(0, 0)`
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let Some(bounds) = self.bounds else {
+            return Grid(Vec::new());
+        };
+
+        let mut grid = Grid::new(bounds.width(), bounds.height(), fill);
+        for (p, t) in self.iter() {
+            let p = Point {
+                x: p.x - bounds.min.x,
+                y: p.y - bounds.min.y,
+            };
+            grid[p] = t.clone();
+        }
+        grid
+    }
+
+    /// writes the cells inside `rect` row by row, using the empty char for unset ones
+    pub fn render(&self, rect: Rect) -> String
+    where
+        T: Display,
+    {
+        let mut s = String::new();
+        let mut rows: Vec<_> = (rect.min.y..=rect.max.y).collect();
+        if self.flip_y {
+            rows.reverse();
+        }
+
+        for y in rows {
+            for x in rect.min.x..=rect.max.x {
+                match self.get(Point { x, y }) {
+                    Some(t) => s.push_str(&t.to_string()),
+                    None => s.push(self.empty),
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+fn grow(bounds: Option<Rect>, p: Point) -> Rect {
+    match bounds {
+        None => Rect::new(p, p),
+        Some(Rect { min, max }) => Rect::new(
+            Point {
+                x: min.x.min(p.x),
+                y: min.y.min(p.y),
+            },
+            Point {
+                x: max.x.max(p.x),
+                y: max.y.max(p.y),
+            },
+        ),
+    }
+}
+
+fn bounds_of(points: impl Iterator<Item = Point>) -> Option<Rect> {
+    points.fold(None, |bounds, p| Some(grow(bounds, p)))
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (p, t) in iter {
+            grid.insert(p, t);
+        }
+        grid
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        grid.0
+            .into_iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.into_iter().enumerate().map(move |(x, t)| {
+                    let p = Point {
+                        x: x as isize,
+                        y: y as isize,
+                    };
+                    (p, t)
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T> Display for SparseGrid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.bounds {
+            Some(bounds) => write!(f, "{}", self.render(bounds)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> SparseGrid<char> {
+        [(-1, 2), (1, 0), (1, 1)]
+            .into_iter()
+            .map(|p| (Point::from_x_y(p), '#'))
+            .collect()
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = grid();
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point { x: -1, y: 0 }, Point { x: 1, y: 2 }))
+        );
+
+        grid.remove(Point { x: 1, y: 1 });
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point { x: -1, y: 0 }, Point { x: 1, y: 2 }))
+        );
+
+        grid.remove(Point { x: -1, y: 2 });
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point { x: 1, y: 0 }, Point { x: 1, y: 0 }))
+        );
+
+        grid.remove(Point { x: 1, y: 0 });
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = grid();
+        assert_eq!(grid.to_string(), "..#\n..#\n#..\n");
+        assert_eq!(
+            grid.with_empty(' ').with_flipped_y().to_string(),
+            "#  \n  #\n  #\n"
+        );
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_grid_conversion() {
+        let dense = grid().to_grid('.');
+        assert_eq!(dense.to_string(), "..#\n..#\n#..\n");
+
+        let sparse = SparseGrid::from(dense.clone());
+        assert_eq!(sparse.len(), 9);
+        assert_eq!(sparse.to_grid(' '), dense);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = grid();
+        let neighbors: Vec<_> = grid
+            .neighbors(Point { x: 0, y: 1 }, Neighborhood::ALL)
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                Point { x: 1, y: 0 },
+                Point { x: 1, y: 1 },
+                Point { x: -1, y: 2 }
+            ]
+        );
+    }
+}
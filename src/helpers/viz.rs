@@ -0,0 +1,283 @@
+//! playing simulations back in the terminal.
+//!
+//! a day builds an iterator of [`Frame`]s, usually from its existing `Display` impls, and hands
+//! it to a [`Player`]. `Player::from_args` picks up the flags, so a day only visualizes when run
+//! like `cargo solve 09 -- --visualize [--fps 30] [--step]`.
+//!
+//! while playing, type enter to pause or resume, `n` then enter to step one frame, and `q` then
+//! enter to stop.
+
+use std::{
+    collections::HashMap,
+    io::{stdin, stdout, Write},
+    process,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use super::Point;
+
+pub const ANSI_CLEAR_SCREEN: &str = "\x1b[2J";
+pub const ANSI_HOME: &str = "\x1b[H";
+pub const ANSI_CLEAR_LINE: &str = "\x1b[K";
+pub const ANSI_CLEAR_BELOW: &str = "\x1b[J";
+pub const ANSI_HIDE_CURSOR: &str = "\x1b[?25l";
+pub const ANSI_SHOW_CURSOR: &str = "\x1b[?25h";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    /// the escape code that makes the following text bold and this color
+    pub fn ansi(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[1;31m",
+            Color::Green => "\x1b[1;32m",
+            Color::Yellow => "\x1b[1;33m",
+            Color::Blue => "\x1b[1;34m",
+            Color::Magenta => "\x1b[1;35m",
+            Color::Cyan => "\x1b[1;36m",
+        }
+    }
+}
+
+/// one picture of the simulation. highlights are by character, so `(x, y)` is the `x`th char on
+/// the `y`th line of the text
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Frame {
+    text: String,
+    highlights: HashMap<Point, Color>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// colors the chars at `points`, over any earlier highlight of the same ones
+    pub fn highlight(mut self, points: impl IntoIterator<Item = Point>, color: Color) -> Self {
+        self.highlights
+            .extend(points.into_iter().map(|p| (p, color)));
+        self
+    }
+
+    /// a line shown under the frame, like the step number
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// the text with the highlights colored in and the caption below it
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        for (y, line) in self.text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let p = Point::new(x as isize, y as isize);
+                match self.highlights.get(&p) {
+                    Some(color) => {
+                        s.push_str(color.ansi());
+                        s.push(c);
+                        s.push_str(crate::ANSI_RESET);
+                    }
+                    None => s.push(c),
+                }
+            }
+            s.push('\n');
+        }
+        if let Some(caption) = &self.caption {
+            s.push_str(crate::ANSI_ITALIC);
+            s.push_str(caption);
+            s.push_str(crate::ANSI_RESET);
+            s.push('\n');
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Player {
+    fps: f64,
+    paused: bool,
+}
+
+impl Player {
+    /// plays at 10 frames per second
+    pub fn new() -> Self {
+        Self {
+            fps: 10.0,
+            paused: false,
+        }
+    }
+
+    /// panics unless `fps` is a finite number above 0
+    pub fn with_fps(mut self, fps: f64) -> Self {
+        self.fps = valid_fps(fps).unwrap_or_else(|e| panic!("{}", e));
+        self
+    }
+
+    /// start paused, so each frame waits for a step
+    pub fn stepping(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// a player set up from `--fps` and `--step`, or `None` if the program wasn't run with
+    /// `--visualize`
+    pub fn from_args() -> Option<Self> {
+        let mut args = pico_args::Arguments::from_env();
+        if !args.contains("--visualize") {
+            return None;
+        }
+
+        let mut player = Self::new();
+        match args.opt_value_from_fn("--fps", |s| {
+            s.parse().map_err(|e| format!("{}", e)).and_then(valid_fps)
+        }) {
+            Ok(Some(fps)) => player = player.with_fps(fps),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Failed to process arguments: {}", e);
+                process::exit(1);
+            }
+        }
+        if args.contains("--step") {
+            player = player.stepping();
+        }
+        Some(player)
+    }
+
+    pub fn play(&self, frames: impl IntoIterator<Item = Frame>) {
+        let controls = controls();
+        let delay = Duration::from_secs_f64(1.0 / self.fps);
+        let mut paused = self.paused;
+        let mut out = stdout().lock();
+
+        write!(out, "{}{}", ANSI_HIDE_CURSOR, ANSI_CLEAR_SCREEN).unwrap();
+        for (i, frame) in frames.into_iter().enumerate() {
+            write!(out, "{}", ANSI_HOME).unwrap();
+            for line in frame.render().lines() {
+                writeln!(out, "{}{}", line, ANSI_CLEAR_LINE).unwrap();
+            }
+            writeln!(
+                out,
+                "frame {}  [enter] pause/resume  [n] step  [q] quit{}",
+                i, ANSI_CLEAR_BELOW
+            )
+            .unwrap();
+            out.flush().unwrap();
+
+            let control = if paused {
+                controls.recv().ok()
+            } else {
+                thread::sleep(delay);
+                controls.try_iter().last()
+            };
+            match control {
+                Some(Control::Toggle) => paused = !paused,
+                Some(Control::Step) => paused = true,
+                Some(Control::Quit) => break,
+                None => {}
+            }
+        }
+        write!(out, "{}", ANSI_SHOW_CURSOR).unwrap();
+        out.flush().unwrap();
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Toggle,
+    Step,
+    Quit,
+}
+
+/// reads lines from stdin in the background, since there's no way to wait for a key without
+/// blocking the animation
+fn controls() -> Receiver<Control> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdin().lines() {
+            let control = match line.as_deref().map(str::trim) {
+                Ok("q") | Err(_) => Control::Quit,
+                Ok("n") => Control::Step,
+                Ok(_) => Control::Toggle,
+            };
+            if tx.send(control).is_err() || control == Control::Quit {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn valid_fps(fps: f64) -> Result<f64, String> {
+    match fps.is_finite() && fps > 0.0 {
+        true => Ok(fps),
+        false => Err(format!("fps has to be a number above 0, not {}", fps)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ANSI_RESET;
+
+    #[test]
+    fn test_render() {
+        let frame = Frame::new("ab\ncd\n")
+            .highlight([Point::new(1, 0), Point::new(0, 1)], Color::Red)
+            .highlight([Point::new(0, 1)], Color::Green);
+        assert_eq!(
+            frame.render(),
+            format!(
+                "a{}b{}\n{}c{}d\n",
+                Color::Red.ansi(),
+                ANSI_RESET,
+                Color::Green.ansi(),
+                ANSI_RESET
+            )
+        );
+
+        let frame = Frame::new("↗.").highlight([Point::new(1, 0)], Color::Blue);
+        assert_eq!(
+            frame.render(),
+            format!("↗{}.{}\n", Color::Blue.ansi(), ANSI_RESET)
+        );
+
+        let frame = Frame::new("x").with_caption("step 1");
+        assert!(frame.render().starts_with("x\n"));
+        assert!(frame.render().contains("step 1"));
+    }
+
+    #[test]
+    fn test_fps() {
+        assert_eq!(Player::new().with_fps(30.0).fps, 30.0);
+        for fps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(valid_fps(fps).is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "fps has to be a number above 0, not 0")]
+    fn test_zero_fps() {
+        Player::new().with_fps(0.0);
+    }
+}